@@ -0,0 +1,321 @@
+use crate::trace::{AccessType, Record};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// Quantos strides diferentes são mostrados no histograma, o resto é somado.
+const SHOWN_STRIDES: usize = 16;
+
+#[derive(Debug)]
+pub struct WorkingSet {
+    // Índice do primeiro acesso da janela.
+    pub start: usize,
+    // Blocos distintos tocados na janela, um valor para cada bsize.
+    pub blocks: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub struct TraceStats {
+    pub length: usize,
+    pub reads: usize,
+    pub writes: usize,
+    pub fetches: usize,
+    pub unique_adresses: usize,
+    pub bsizes: Vec<usize>,
+    // Blocos distintos no trace inteiro, um valor para cada bsize.
+    pub unique_blocks: Vec<usize>,
+    pub window: usize,
+    pub working_set: Vec<WorkingSet>,
+    // Diferença entre endereços consecutivos -> quantas vezes aconteceu.
+    pub strides: BTreeMap<i64, usize>,
+}
+
+impl TraceStats {
+    pub fn compute(input: &[Record], bsizes: &[usize], window: usize) -> TraceStats {
+        let nbits_offsets: Vec<usize> = bsizes
+            .iter()
+            .map(|bsize| bsize.trailing_zeros() as usize)
+            .collect();
+
+        let mut stats = TraceStats {
+            length: input.len(),
+            reads: 0,
+            writes: 0,
+            fetches: 0,
+            unique_adresses: 0,
+            bsizes: bsizes.to_vec(),
+            unique_blocks: Vec::new(),
+            window,
+            working_set: Vec::new(),
+            strides: BTreeMap::new(),
+        };
+
+        let mut adresses = HashSet::new();
+        let mut blocks: Vec<HashSet<u64>> = vec![HashSet::new(); bsizes.len()];
+        let mut previous: Option<u64> = None;
+
+        for (start, chunk) in input.chunks(window.max(1)).enumerate() {
+            let mut window_blocks: Vec<HashSet<u64>> = vec![HashSet::new(); bsizes.len()];
+
+            for record in chunk {
+                match record.access {
+                    AccessType::Read => stats.reads += 1,
+                    AccessType::Write => stats.writes += 1,
+                    AccessType::Fetch => stats.fetches += 1,
+                }
+
+                adresses.insert(record.adress);
+                for (index, &nbits_offset) in nbits_offsets.iter().enumerate() {
+                    for block in record.blocks(nbits_offset) {
+                        blocks[index].insert(block);
+                        window_blocks[index].insert(block);
+                    }
+                }
+
+                if let Some(previous) = previous {
+                    *stats
+                        .strides
+                        .entry(record.adress.wrapping_sub(previous) as i64)
+                        .or_insert(0) += 1;
+                }
+                previous = Some(record.adress);
+            }
+
+            stats.working_set.push(WorkingSet {
+                start: start * window.max(1),
+                blocks: window_blocks.iter().map(HashSet::len).collect(),
+            });
+        }
+
+        stats.unique_adresses = adresses.len();
+        stats.unique_blocks = blocks.iter().map(HashSet::len).collect();
+
+        stats
+    }
+
+    pub fn print(&self) {
+        let percent = |count: usize, total: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                count as f64 * 100.0 / total as f64
+            }
+        };
+
+        println!("length = {}", self.length);
+        println!(
+            "reads = {} ({:.2}%)",
+            self.reads,
+            percent(self.reads, self.length)
+        );
+        println!(
+            "writes = {} ({:.2}%)",
+            self.writes,
+            percent(self.writes, self.length)
+        );
+        println!(
+            "fetches = {} ({:.2}%)",
+            self.fetches,
+            percent(self.fetches, self.length)
+        );
+        println!("unique adresses = {}", self.unique_adresses);
+
+        println!();
+        println!("{:>10} {:>14}", "bsize", "unique blocks");
+        for (bsize, blocks) in self.bsizes.iter().zip(&self.unique_blocks) {
+            println!("{:>10} {:>14}", bsize, blocks);
+        }
+
+        println!();
+        println!(
+            "working set (distinct blocks per window of {} accesses)",
+            self.window
+        );
+        print!("{:>10}", "start");
+        for bsize in &self.bsizes {
+            print!(" {:>10}", format!("bsize {}", bsize));
+        }
+        println!();
+        for window in &self.working_set {
+            print!("{:>10}", window.start);
+            for blocks in &window.blocks {
+                print!(" {:>10}", blocks);
+            }
+            println!();
+        }
+
+        let total_strides: usize = self.strides.values().sum();
+        let mut strides: Vec<(&i64, &usize)> = self.strides.iter().collect();
+        // Mais frequentes primeiro, empates pelo menor stride.
+        strides.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+        println!();
+        println!(
+            "strides (top {} of {} distinct)",
+            SHOWN_STRIDES.min(strides.len()),
+            strides.len()
+        );
+        println!("{:>21} {:>10} {:>8}", "stride", "count", "%");
+        for (stride, count) in strides.iter().take(SHOWN_STRIDES) {
+            println!(
+                "{:>21} {:>10} {:>7.2}%",
+                stride,
+                count,
+                percent(**count, total_strides)
+            );
+        }
+        if strides.len() > SHOWN_STRIDES {
+            let others: usize = strides.iter().skip(SHOWN_STRIDES).map(|(_, c)| **c).sum();
+            println!(
+                "{:>21} {:>10} {:>7.2}%",
+                "others",
+                others,
+                percent(others, total_strides)
+            );
+        }
+    }
+}
+
+// Árvore de Fenwick para contar quantos blocos têm o último uso dentro de um
+// intervalo de posições do trace, em O(log n).
+struct Fenwick {
+    tree: Vec<i64>,
+}
+
+impl Fenwick {
+    fn new(size: usize) -> Fenwick {
+        Fenwick {
+            tree: vec![0; size + 1],
+        }
+    }
+
+    fn add(&mut self, position: usize, value: i64) {
+        let mut index = position + 1;
+        while index < self.tree.len() {
+            self.tree[index] += value;
+            index += index & index.wrapping_neg();
+        }
+    }
+
+    // Soma das posições [0, position).
+    fn prefix_sum(&self, position: usize) -> i64 {
+        let mut index = position;
+        let mut sum = 0;
+        while index > 0 {
+            sum += self.tree[index];
+            index -= index & index.wrapping_neg();
+        }
+        sum
+    }
+}
+
+#[derive(Debug)]
+pub struct ReuseProfile {
+    pub bsize: usize,
+    // Referências a blocos, acessos que cruzam blocos contam uma vez por bloco.
+    pub references: usize,
+    // Primeiras referências a cada bloco (distância infinita).
+    pub cold: usize,
+    // histogram[d] = referências com distância de pilha d, ou seja, com d
+    // blocos distintos usados desde a referência anterior ao mesmo bloco.
+    pub histogram: Vec<usize>,
+}
+
+impl ReuseProfile {
+    pub fn compute(input: &[Record], bsize: usize) -> ReuseProfile {
+        let nbits_offset = bsize.trailing_zeros() as usize;
+        let total: usize = input
+            .iter()
+            .map(|record| {
+                let blocks = record.blocks(nbits_offset);
+                (blocks.end() - blocks.start()) as usize + 1
+            })
+            .sum();
+
+        let mut profile = ReuseProfile {
+            bsize,
+            references: total,
+            cold: 0,
+            histogram: Vec::new(),
+        };
+
+        // Cada bloco tem um 1 marcado na posição do seu último uso, assim a
+        // distância é a soma entre o uso anterior e o atual.
+        let mut last_use: HashMap<u64, usize> = HashMap::new();
+        let mut marks = Fenwick::new(total);
+        let mut position = 0;
+
+        for record in input {
+            for block in record.blocks(nbits_offset) {
+                match last_use.insert(block, position) {
+                    Some(previous) => {
+                        let distance =
+                            (marks.prefix_sum(position) - marks.prefix_sum(previous + 1)) as usize;
+                        if profile.histogram.len() <= distance {
+                            profile.histogram.resize(distance + 1, 0);
+                        }
+                        profile.histogram[distance] += 1;
+                        marks.add(previous, -1);
+                    }
+                    None => profile.cold += 1,
+                }
+                marks.add(position, 1);
+                position += 1;
+            }
+        }
+
+        profile
+    }
+
+    // Misses de uma cache totalmente associativa LRU com `blocks` blocos,
+    // que erra exatamente as referências com distância >= blocks.
+    pub fn misses(&self, blocks: usize) -> usize {
+        self.cold + self.histogram.iter().skip(blocks).sum::<usize>()
+    }
+
+    pub fn miss_ratio(&self, blocks: usize) -> f64 {
+        if self.references == 0 {
+            0.0
+        } else {
+            self.misses(blocks) as f64 / self.references as f64
+        }
+    }
+
+    pub fn print(&self) {
+        println!("bsize = {}", self.bsize);
+        println!("references = {}", self.references);
+        println!("cold = {}", self.cold);
+
+        // O histograma é mostrado em faixas de potência de 2: [0], [1], [2, 3], ...
+        println!();
+        println!("{:>23} {:>10}", "distance", "count");
+        let mut low = 0;
+        while low < self.histogram.len() {
+            let high = if low == 0 { 0 } else { low * 2 - 1 };
+            let count: usize = self.histogram.iter().skip(low).take(high - low + 1).sum();
+            println!("{:>23} {:>10}", format!("[{}, {}]", low, high), count);
+            low = high + 1;
+        }
+        println!("{:>23} {:>10}", "inf", self.cold);
+
+        // Todo tamanho tem o seu miss ratio, mostra as potências de 2 até o
+        // tamanho em que só sobram os misses compulsórios.
+        println!();
+        println!(
+            "{:>12} {:>14} {:>10} {:>12}",
+            "blocks", "bytes", "misses", "miss ratio"
+        );
+        let mut blocks = 1;
+        loop {
+            println!(
+                "{:>12} {:>14} {:>10} {:>12.6}",
+                blocks,
+                blocks * self.bsize,
+                self.misses(blocks),
+                self.miss_ratio(blocks)
+            );
+            if blocks >= self.histogram.len() {
+                break;
+            }
+            blocks *= 2;
+        }
+    }
+}
@@ -5,6 +5,16 @@ pub enum ReplacementPolicy {
     Random,
 }
 
+impl std::fmt::Display for ReplacementPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ReplacementPolicy::Lru => write!(f, "lru"),
+            ReplacementPolicy::Fifo => write!(f, "fifo"),
+            ReplacementPolicy::Random => write!(f, "random"),
+        }
+    }
+}
+
 trait Conjunto {
     fn has_tag(&self, tag: usize) -> bool;
     fn get_index_by_tag(&self, tag: usize) -> Option<usize>;
@@ -23,7 +33,7 @@ impl Conjunto for Vec<Data> {
     fn get_index_by_tag(&self, tag: usize) -> Option<usize> {
         self.iter()
             .enumerate()
-            .find(|(_, elem)| elem.tag == tag)
+            .find(|(_, elem)| elem.is_initialized && elem.tag == tag)
             .map(|(index, _)| index.to_owned())
     }
 
@@ -102,18 +112,26 @@ impl Conjunto for Vec<Data> {
     fn get_highest_replaceability_index(&self) -> Option<usize> {
         self.iter()
             .enumerate()
-            .scan(None, |state: &mut Option<usize>, (index, elem)| {
-                if elem.is_initialized && !(state.is_some() && state.unwrap() > elem.replaceability)
-                {
-                    *state = Some(index);
-                }
-
-                *state
-            })
-            .last()
+            .filter(|(_, elem)| elem.is_initialized)
+            .max_by_key(|(_, elem)| elem.replaceability)
+            .map(|(index, _)| index)
     }
 }
 
+// Caminho rápido da cache totalmente associativa (nsets = 1), onde percorrer
+// as vias do conjunto a cada acesso fica lento demais com milhares de vias.
+// A replaceability das linhas é o instante (Cache::clock) do último uso (Lru)
+// ou da inserção (Fifo), como no access_skewed.
+#[derive(Debug, Default)]
+pub struct TagMap {
+    // Via de cada tag presente na cache.
+    ways: std::collections::HashMap<usize, usize>,
+    // Vias ocupadas por ordem de replaceability, a primeira é a vítima.
+    order: std::collections::BTreeMap<usize, usize>,
+    // As vias são ocupadas em ordem, as vagas são filled.. em diante.
+    filled: usize,
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Kind {
     Data,
@@ -121,7 +139,36 @@ pub enum Kind {
     Both,
 }
 
-#[derive(Default, Debug)]
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Kind::Data => write!(f, "data"),
+            Kind::Instruction => write!(f, "instruction"),
+            Kind::Both => write!(f, "both"),
+        }
+    }
+}
+
+// Como os blocos são distribuídos nas vias.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Organisation {
+    // Cada bloco tem um conjunto só, com todas as vias dele.
+    #[default]
+    SetAssociative,
+    // Cada via tem a sua própria função de índice (ver skewed::indexes).
+    Skewed,
+}
+
+impl std::fmt::Display for Organisation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Organisation::SetAssociative => write!(f, "set-associative"),
+            Organisation::Skewed => write!(f, "skewed"),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone)]
 pub struct Performance {
     pub slots_occupied: usize,
     pub accesses: usize,
@@ -130,6 +177,75 @@ pub struct Performance {
     pub compulsory_misses: usize,
     pub capacity_misses: usize,
     pub conflict_misses: usize,
+    // Só com setores: a tag estava na cache mas o sub-bloco não, esses
+    // misses não entram em compulsory, capacity e conflict.
+    pub sub_block_misses: usize,
+    // Só com setores: bytes dos sub-blocos que nunca foram buscados enquanto
+    // o setor estava na cache, que uma linha inteira teria buscado à toa.
+    pub unfetched_bytes: usize,
+    // Só com setores: bytes dos sub-blocos sujos escritos de volta quando o
+    // setor saiu da cache.
+    pub written_back_bytes: usize,
+    // Acessos que cruzaram a fronteira de um bloco e foram divididos,
+    // cada pedaço conta como um acesso separado em accesses.
+    pub split_accesses: usize,
+}
+
+impl Performance {
+    // Quanto cada contador andou desde `earlier`, slots_occupied fica com o
+    // valor atual porque não é um contador.
+    pub fn since(&self, earlier: &Performance) -> Performance {
+        Performance {
+            slots_occupied: self.slots_occupied,
+            accesses: self.accesses - earlier.accesses,
+            hits: self.hits - earlier.hits,
+            misses: self.misses - earlier.misses,
+            compulsory_misses: self.compulsory_misses - earlier.compulsory_misses,
+            capacity_misses: self.capacity_misses - earlier.capacity_misses,
+            conflict_misses: self.conflict_misses - earlier.conflict_misses,
+            sub_block_misses: self.sub_block_misses - earlier.sub_block_misses,
+            unfetched_bytes: self.unfetched_bytes - earlier.unfetched_bytes,
+            written_back_bytes: self.written_back_bytes - earlier.written_back_bytes,
+            split_accesses: self.split_accesses - earlier.split_accesses,
+        }
+    }
+
+    pub fn format(&self, verbosity: u8) -> String {
+        // Sem acessos (ou sem misses) as taxas ficam 0 ao invez de NaN.
+        let rate =
+            |numerator, denominator| crate::report::rate(numerator, denominator).unwrap_or(0.0);
+
+        if verbosity == 1 {
+            format!(
+                "{}, {}, {}, {}, {}, {}",
+                self.accesses,
+                rate(self.hits, self.accesses),
+                rate(self.misses, self.accesses),
+                rate(self.compulsory_misses, self.misses),
+                rate(self.capacity_misses, self.misses),
+                rate(self.conflict_misses, self.misses)
+            )
+        } else {
+            format!("{:#?}", self)
+        }
+    }
+}
+
+// Contadores de um conjunto só, indexado igual a Cache::data.
+#[derive(Default, Debug, Clone)]
+pub struct SetPerformance {
+    pub hits: usize,
+    pub misses: usize,
+    // Misses que tiraram um bloco válido do conjunto.
+    pub evictions: usize,
+    // Os evictions que foram misses de conflito.
+    pub conflicts: usize,
+}
+
+impl SetPerformance {
+    pub fn accesses(&self) -> usize {
+        self.hits + self.misses
+    }
 }
 
 #[derive(Debug)]
@@ -139,7 +255,17 @@ pub struct Info {
     pub repl: ReplacementPolicy,
     pub assoc: usize,
     pub size: usize, // in bytes
+    // Tamanho do sub-bloco com setores, igual a bsize sem.
+    pub sub_block: usize,
+    // Como o run_with calcula o índice, só para os relatórios.
+    pub index: crate::indexing::IndexFunction,
+    pub organisation: Organisation,
+    // A compressão que o run_with usou, também só para os relatórios.
+    pub compression: Option<crate::compression::Compression>,
+    pub translation: Option<crate::tlb::Translation>,
     pub total_slots: usize,
+    // Seed usada para criar rng, guardada para poder reproduzir a simulação.
+    pub seed: u64,
     pub rng: rand::rngs::StdRng,
 }
 
@@ -149,14 +275,44 @@ pub struct Data {
     pub is_initialized: bool,
     // Higher means more likely to be replaced
     pub replaceability: usize,
+    // Um bit por sub-bloco do setor, só usados com setores.
+    pub valid_sub_blocks: u64,
+    pub dirty_sub_blocks: u64,
+    // Bytes que a linha ocupa, só usado na cache comprimida.
+    pub compressed_size: usize,
 }
 
 #[derive(Debug)]
 pub struct Cache {
     pub kind: Kind,
     pub performance: Performance,
+    // Enquanto warming_up for true os acessos mudam o estado da cache mas são
+    // contados em warmup_performance ao invez de performance.
+    pub warming_up: bool,
+    pub warmup_performance: Performance,
+    pub set_performance: Vec<SetPerformance>,
+    // Preenchido pelo run_with quando RunOptions::window é usado.
+    pub windows: Vec<crate::timeline::Window>,
+    // Contadores de cada janela medida da amostragem temporal.
+    pub samples: Vec<Performance>,
     pub info: Info,
     pub data: Vec<Vec<Data>>,
+    // Quantos acessos access_skewed ou o caminho rápido já fizeram, é o
+    // relógio do Lru e do Fifo deles.
+    pub clock: usize,
+    // Só existe quando nsets = 1, None usa o caminho normal do Conjunto.
+    pub tag_map: Option<TagMap>,
+    // Preenchido pelo run_with quando RunOptions::way_predictor é usado.
+    pub way_predictor: Option<crate::waypred::WayPredictor>,
+    // Preenchido pelo run_with quando RunOptions::technology é usado.
+    pub energy: Option<crate::energy::Estimate>,
+    // Preenchido pelo run_with quando RunOptions::compression é usado.
+    pub compression: Option<crate::compression::Stats>,
+    // Preenchido pelo run_with quando RunOptions::translation é usado.
+    pub translation: Option<crate::tlb::Stats>,
+    // Blocos (conjunto, tag) que já passaram pela cache comprimida, o
+    // access_compressed classifica os misses com eles.
+    pub seen_blocks: std::collections::HashSet<(usize, usize)>,
 }
 
 impl Cache {
@@ -189,13 +345,24 @@ impl Cache {
         Cache {
             kind,
             performance: Default::default(),
+            warming_up: false,
+            warmup_performance: Default::default(),
+            set_performance: vec![Default::default(); nsets],
+            windows: Vec::new(),
+            samples: Vec::new(),
             info: Info {
                 nsets,
                 bsize,
                 repl,
                 assoc,
                 size: bsize * nsets * assoc,
+                sub_block: bsize,
+                index: Default::default(),
+                organisation: Default::default(),
+                compression: None,
+                translation: None,
                 total_slots: nsets * assoc,
+                seed: random_repl_seed,
                 rng: rand::SeedableRng::seed_from_u64(random_repl_seed),
             },
             data: {
@@ -208,6 +375,9 @@ impl Cache {
                             tag: 0,
                             is_initialized: false,
                             replaceability: 0,
+                            valid_sub_blocks: 0,
+                            dirty_sub_blocks: 0,
+                            compressed_size: 0,
                         })
                     }
                     vec.push(conjunto);
@@ -215,56 +385,403 @@ impl Cache {
 
                 vec
             },
+            clock: 0,
+            tag_map: if nsets == 1 {
+                Some(Default::default())
+            } else {
+                None
+            },
+            way_predictor: None,
+            energy: None,
+            compression: None,
+            translation: None,
+            seen_blocks: Default::default(),
         }
     }
 
+    pub fn format_perf(&self, verbosity: u8) -> String {
+        self.performance.format(verbosity)
+    }
+
     pub fn print_perf(&self, verbosity: u8) {
-        if verbosity == 1 {
-            println!(
-                "{}, {}, {}, {}, {}, {}",
-                self.performance.accesses,
-                self.performance.hits as f64 / self.performance.accesses as f64,
-                self.performance.misses as f64 / self.performance.accesses as f64,
-                self.performance.compulsory_misses as f64 / self.performance.misses as f64,
-                self.performance.capacity_misses as f64 / self.performance.misses as f64,
-                self.performance.conflict_misses as f64 / self.performance.misses as f64
-            );
+        println!("{}", self.format_perf(verbosity));
+    }
+
+    // Os contadores que o acesso atual deve incrementar.
+    pub fn counters_mut(&mut self) -> &mut Performance {
+        if self.warming_up {
+            &mut self.warmup_performance
         } else {
-            println!("{:#?}", self.performance);
+            &mut self.performance
         }
     }
 
+    // Fim do aquecimento, os próximos acessos entram em performance.
+    pub fn end_warmup(&mut self) {
+        self.warming_up = false;
+        self.warmup_performance.slots_occupied = self.performance.slots_occupied;
+    }
+
     pub fn access_with(&mut self, index: usize, tag: usize, _offset: usize) -> AccessResult {
-        self.performance.accesses += 1;
+        let result = if self.has_tag(index, tag) {
+            self.insert_tag(index, tag);
 
-        if self.data[index].has_tag(tag) {
-            self.performance.hits += 1;
+            AccessResult::Hit
+        } else if self.has_vacancy(index) {
+            // Ocupa o slot porque ele vai ser enchido, mesmo durante o
+            // aquecimento porque é estado da cache e não um contador.
+            self.performance.slots_occupied += 1;
+            self.insert_tag(index, tag);
 
-            self.data[index].insert_tag(tag, self.info.repl, &mut self.info.rng);
+            AccessResult::Miss(MissTypes::Compulsory)
+        } else if self.performance.slots_occupied == self.info.total_slots {
+            self.insert_tag(index, tag);
 
-            AccessResult::Hit
+            AccessResult::Miss(MissTypes::Capacity)
         } else {
-            self.performance.misses += 1;
+            self.insert_tag(index, tag);
 
-            if self.data[index].uninitialized_slots() > 0 {
-                self.performance.compulsory_misses += 1;
-                // Ocupa o slot porque ele vai ser enchido
-                self.performance.slots_occupied += 1;
-                self.data[index].insert_tag(tag, self.info.repl, &mut self.info.rng);
+            AccessResult::Miss(MissTypes::Conflict)
+        };
 
-                AccessResult::Miss(MissTypes::Compulsory)
-            } else if self.performance.slots_occupied == self.info.total_slots {
-                self.performance.capacity_misses += 1;
+        self.count(index, &result);
+        result
+    }
+
+    // Via do conjunto `index` que tem a tag.
+    pub fn way_of(&self, index: usize, tag: usize) -> Option<usize> {
+        match &self.tag_map {
+            Some(map) => map.ways.get(&tag).copied(),
+            None => self.data[index].get_index_by_tag(tag),
+        }
+    }
+
+    fn has_tag(&self, index: usize, tag: usize) -> bool {
+        match &self.tag_map {
+            Some(map) => map.ways.contains_key(&tag),
+            None => self.data[index].has_tag(tag),
+        }
+    }
+
+    fn has_vacancy(&self, index: usize) -> bool {
+        match &self.tag_map {
+            Some(map) => map.filled < self.info.assoc,
+            None => self.data[index].uninitialized_slots() > 0,
+        }
+    }
 
+    // Insere (ou usa, quando já está na cache) a tag e retorna a via dela.
+    fn insert_tag(&mut self, index: usize, tag: usize) -> usize {
+        let map = match &mut self.tag_map {
+            Some(map) => map,
+            None => {
                 self.data[index].insert_tag(tag, self.info.repl, &mut self.info.rng);
+                return self.data[index].get_index_by_tag(tag).unwrap();
+            }
+        };
+        let set = &mut self.data[index];
+        self.clock += 1;
+
+        if let Some(&way) = map.ways.get(&tag) {
+            if self.info.repl == ReplacementPolicy::Lru {
+                map.order.remove(&set[way].replaceability);
+                map.order.insert(self.clock, way);
+                set[way].replaceability = self.clock;
+            }
+            return way;
+        }
 
+        let way = if map.filled < set.len() {
+            map.filled += 1;
+            map.filled - 1
+        } else {
+            match self.info.repl {
+                // Igual ao choose_mut do Conjunto, para a mesma seed escolher
+                // as mesmas vítimas.
+                ReplacementPolicy::Random => {
+                    use rand::Rng;
+                    self.info.rng.gen_range(0, set.len() as u32) as usize
+                }
+                ReplacementPolicy::Lru | ReplacementPolicy::Fifo => {
+                    *map.order.values().next().unwrap()
+                }
+            }
+        };
+
+        let line = &mut set[way];
+        if line.is_initialized {
+            map.ways.remove(&line.tag);
+            map.order.remove(&line.replaceability);
+        }
+        line.is_initialized = true;
+        line.tag = tag;
+        line.replaceability = self.clock;
+        map.ways.insert(tag, way);
+        map.order.insert(self.clock, way);
+
+        way
+    }
+
+    // Acesso na organização skewed-associative: `indexes[via]` é o conjunto do
+    // bloco em cada via e a tag é o bloco inteiro. data[conjunto][via] guarda
+    // as linhas como na organização normal, mas como os conjuntos de cada via
+    // não se alinham a replaceability é o instante (clock) do último uso
+    // (Lru) ou da inserção (Fifo) e a vítima é a candidata mais antiga.
+    pub fn access_skewed(&mut self, indexes: &[usize], tag: usize) -> AccessResult {
+        self.clock += 1;
+
+        let hit = (0..indexes.len()).find(|&way| {
+            let line = &self.data[indexes[way]][way];
+            line.is_initialized && line.tag == tag
+        });
+        if let Some(way) = hit {
+            if self.info.repl == ReplacementPolicy::Lru {
+                self.data[indexes[way]][way].replaceability = self.clock;
+            }
+            self.count(indexes[way], &AccessResult::Hit);
+            return AccessResult::Hit;
+        }
+
+        let vacant = (0..indexes.len()).find(|&way| !self.data[indexes[way]][way].is_initialized);
+        let (way, result) = match vacant {
+            Some(way) => {
+                self.performance.slots_occupied += 1;
+                (way, AccessResult::Miss(MissTypes::Compulsory))
+            }
+            None => {
+                let way = match self.info.repl {
+                    ReplacementPolicy::Random => {
+                        use rand::Rng;
+                        self.info.rng.gen_range(0, indexes.len())
+                    }
+                    ReplacementPolicy::Lru | ReplacementPolicy::Fifo => (0..indexes.len())
+                        .min_by_key(|&way| self.data[indexes[way]][way].replaceability)
+                        .unwrap(),
+                };
+                let kind = if self.performance.slots_occupied == self.info.total_slots {
+                    MissTypes::Capacity
+                } else {
+                    MissTypes::Conflict
+                };
+                (way, AccessResult::Miss(kind))
+            }
+        };
+
+        let line = &mut self.data[indexes[way]][way];
+        line.is_initialized = true;
+        line.tag = tag;
+        line.replaceability = self.clock;
+
+        self.count(indexes[way], &result);
+        result
+    }
+
+    // Prepara a cache para access_compressed: cada conjunto ganha mais assoc
+    // tags, os bytes continuam sendo assoc * bsize.
+    pub fn enable_compression(&mut self) {
+        let assoc = self.info.assoc;
+        for set in &mut self.data {
+            set.extend((0..assoc).map(|_| Data {
+                tag: 0,
+                is_initialized: false,
+                replaceability: 0,
+                valid_sub_blocks: 0,
+                dirty_sub_blocks: 0,
+                compressed_size: 0,
+            }));
+        }
+        // O caminho rápido não conhece as linhas de tamanho variável.
+        self.tag_map = None;
+    }
+
+    // Acesso na cache comprimida, `size` é quantos bytes a linha do bloco
+    // ocupa comprimida (ver compression::line_size). Como na skewed a
+    // replaceability é o instante do último uso (Lru) ou da inserção (Fifo).
+    // O primeiro miss de cada bloco é compulsório e os outros são de
+    // capacidade depois que a cache já viu tantos blocos quanto caberiam sem
+    // compressão. Não dá para usar as linhas tiradas: uma linha que diminuiu
+    // abre espaço para um bloco que já foi tirado voltar sem tirar nenhuma.
+    pub fn access_compressed(&mut self, index: usize, tag: usize, size: usize) -> AccessResult {
+        self.clock += 1;
+        let capacity = self.info.assoc * self.info.bsize;
+        let set = &mut self.data[index];
+        let rng = &mut self.info.rng;
+
+        let hit = set.get_index_by_tag(tag);
+        if let Some(way) = hit {
+            if self.info.repl == ReplacementPolicy::Lru {
+                set[way].replaceability = self.clock;
+            }
+            // Uma escrita pode ter mudado o tamanho comprimido.
+            set[way].compressed_size = size;
+        }
+
+        // Tira linhas até a nova (ou a que cresceu) caber nos bytes e nas tags.
+        loop {
+            let used: usize = set
+                .iter()
+                .filter(|line| line.is_initialized)
+                .map(|line| line.compressed_size)
+                .sum::<usize>()
+                + if hit.is_none() { size } else { 0 };
+            let has_tag = hit.is_some() || set.iter().any(|line| !line.is_initialized);
+            if used <= capacity && has_tag {
+                break;
+            }
+
+            let candidates: Vec<usize> = (0..set.len())
+                .filter(|&way| set[way].is_initialized && Some(way) != hit)
+                .collect();
+            let victim = match self.info.repl {
+                ReplacementPolicy::Random => {
+                    use rand::Rng;
+                    candidates[rng.gen_range(0, candidates.len())]
+                }
+                ReplacementPolicy::Lru | ReplacementPolicy::Fifo => *candidates
+                    .iter()
+                    .min_by_key(|&&way| set[way].replaceability)
+                    .unwrap(),
+            };
+            set[victim].is_initialized = false;
+        }
+
+        let result = if hit.is_some() {
+            AccessResult::Hit
+        } else {
+            let way = set.first_vacant_slot_index().unwrap();
+            let line = &mut set[way];
+            line.is_initialized = true;
+            line.tag = tag;
+            line.replaceability = self.clock;
+            line.compressed_size = size;
+
+            let first_reference = self.seen_blocks.insert((index, tag));
+            // Como nos outros caminhos, quantas linhas sem compressão a cache
+            // já encheu.
+            self.performance.slots_occupied = self.seen_blocks.len().min(self.info.total_slots);
+            if first_reference {
+                AccessResult::Miss(MissTypes::Compulsory)
+            } else if self.seen_blocks.len() >= self.info.total_slots {
                 AccessResult::Miss(MissTypes::Capacity)
             } else {
-                self.performance.conflict_misses += 1;
+                AccessResult::Miss(MissTypes::Conflict)
+            }
+        };
 
-                self.data[index].insert_tag(tag, self.info.repl, &mut self.info.rng);
+        self.count(index, &result);
+        result
+    }
 
-                AccessResult::Miss(MissTypes::Conflict)
+    // Acesso numa cache com setores: a tag cobre o bloco inteiro (o setor) e
+    // cada sub-bloco tem os seus bits de válido e sujo, um miss busca só o
+    // sub-bloco acessado.
+    pub fn access_sectored(
+        &mut self,
+        index: usize,
+        tag: usize,
+        sub_block: usize,
+        write: bool,
+    ) -> AccessResult {
+        let bit = 1u64 << sub_block;
+
+        if self.has_tag(index, tag) {
+            let way = self.insert_tag(index, tag);
+            let line = &mut self.data[index][way];
+            let result = if line.valid_sub_blocks & bit != 0 {
+                AccessResult::Hit
+            } else {
+                line.valid_sub_blocks |= bit;
+                AccessResult::Miss(MissTypes::SubBlock)
+            };
+            if write {
+                line.dirty_sub_blocks |= bit;
+            }
+
+            self.count(index, &result);
+            return result;
+        }
+
+        // A classificação é a mesma do access_with.
+        let kind = if self.has_vacancy(index) {
+            self.performance.slots_occupied += 1;
+            MissTypes::Compulsory
+        } else if self.performance.slots_occupied == self.info.total_slots {
+            MissTypes::Capacity
+        } else {
+            MissTypes::Conflict
+        };
+
+        // insert_tag não mexe nos bits dos sub-blocos, então a linha ainda tem
+        // os do setor que saiu.
+        let way = self.insert_tag(index, tag);
+        if kind != MissTypes::Compulsory {
+            self.retire_sector(index, way);
+        }
+        let line = &mut self.data[index][way];
+        line.valid_sub_blocks = bit;
+        line.dirty_sub_blocks = if write { bit } else { 0 };
+
+        let result = AccessResult::Miss(kind);
+        self.count(index, &result);
+        result
+    }
+
+    // Conta os bytes não buscados e os escritos de volta do setor que está em
+    // data[index][way].
+    fn retire_sector(&mut self, index: usize, way: usize) {
+        let sub_blocks = self.info.bsize / self.info.sub_block;
+        let sub_block = self.info.sub_block;
+        let line = &self.data[index][way];
+        let (valid, dirty) = (line.valid_sub_blocks, line.dirty_sub_blocks);
+
+        let performance = self.counters_mut();
+        performance.unfetched_bytes += (sub_blocks - valid.count_ones() as usize) * sub_block;
+        performance.written_back_bytes += dirty.count_ones() as usize * sub_block;
+    }
+
+    // Tira todos os setores da cache no fim da simulação, para que os que
+    // ficaram também entrem em unfetched_bytes e written_back_bytes.
+    pub fn flush_sectors(&mut self) {
+        for index in 0..self.data.len() {
+            for way in 0..self.data[index].len() {
+                if self.data[index][way].is_initialized {
+                    self.retire_sector(index, way);
+                }
+            }
+        }
+    }
+
+    fn count(&mut self, index: usize, result: &AccessResult) {
+        let performance = self.counters_mut();
+        performance.accesses += 1;
+        match result {
+            AccessResult::Hit => performance.hits += 1,
+            AccessResult::Miss(kind) => {
+                performance.misses += 1;
+                match kind {
+                    MissTypes::Compulsory => performance.compulsory_misses += 1,
+                    MissTypes::Capacity => performance.capacity_misses += 1,
+                    MissTypes::Conflict => performance.conflict_misses += 1,
+                    MissTypes::SubBlock => performance.sub_block_misses += 1,
+                }
+            }
+        }
+
+        // Os contadores por conjunto também ignoram o aquecimento.
+        if self.warming_up {
+            return;
+        }
+        let set = &mut self.set_performance[index];
+        match result {
+            AccessResult::Hit => set.hits += 1,
+            AccessResult::Miss(kind) => {
+                set.misses += 1;
+                if *kind == MissTypes::Capacity || *kind == MissTypes::Conflict {
+                    set.evictions += 1;
+                }
+                if *kind == MissTypes::Conflict {
+                    set.conflicts += 1;
+                }
             }
         }
     }
@@ -281,4 +798,6 @@ pub enum MissTypes {
     Compulsory,
     Capacity,
     Conflict,
+    // Só com setores, a tag estava na cache mas o sub-bloco não.
+    SubBlock,
 }
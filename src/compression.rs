@@ -0,0 +1,286 @@
+// Cache comprimida: cada conjunto guarda assoc * bsize bytes de linhas
+// comprimidas e tem o dobro de tags, então cabem mais linhas quanto mais
+// compressíveis forem os dados.
+
+// As linhas comprimidas ocupam um número inteiro de segmentos desse tamanho.
+pub const SEGMENT: usize = 8;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Algorithm {
+    // Base-delta-immediate: palavras de 8, 4 ou 2 bytes viram uma base e
+    // deltas de 1, 2 ou 4 bytes em relação a ela ou a zero.
+    Bdi,
+    // Frequent pattern compression: cada palavra de 4 bytes vira um prefixo de
+    // 3 bits e a parte que importa dela (nada se for zero).
+    Fpc,
+}
+
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Algorithm::Bdi => write!(f, "bdi"),
+            Algorithm::Fpc => write!(f, "fpc"),
+        }
+    }
+}
+
+// De onde vêm os dados das linhas.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Values {
+    // Os valores do trace (Record::value), os bytes nunca vistos são 0.
+    Trace,
+    // Cada bloco é, pelo número dele, zerado com probabilidade `zero`, de
+    // valores próximos uns dos outros com probabilidade `narrow` ou aleatório.
+    Synthetic { zero: f64, narrow: f64 },
+}
+
+// Como no --value-model, 'zero,narrow', ou 'trace'.
+impl std::fmt::Display for Values {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Values::Trace => write!(f, "trace"),
+            Values::Synthetic { zero, narrow } => write!(f, "{},{}", zero, narrow),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Compression {
+    pub algorithm: Algorithm,
+    pub values: Values,
+}
+
+// Valor de `value` interpretado como inteiro com sinal de `bytes` bytes.
+fn sign_extend(value: u64, bytes: usize) -> i64 {
+    let shift = 64 - bytes * 8;
+    ((value << shift) as i64) >> shift
+}
+
+fn fits(value: i64, bytes: usize) -> bool {
+    let limit = 1i64 << (bytes * 8 - 1);
+    -limit <= value && value < limit
+}
+
+fn bdi_size(line: &[u8]) -> usize {
+    if line.iter().all(|&byte| byte == 0) {
+        return 1;
+    }
+
+    let words = |base: usize| -> Vec<u64> {
+        line.chunks(base)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(0u64, |acc, &byte| (acc << 8) | u64::from(byte))
+            })
+            .collect()
+    };
+
+    let mut best = line.len();
+    if line.len() % 8 == 0 && words(8).windows(2).all(|pair| pair[0] == pair[1]) {
+        best = best.min(8);
+    }
+
+    for &(base, delta) in &[(8, 1), (8, 2), (8, 4), (4, 1), (4, 2), (2, 1)] {
+        if line.len() % base != 0 {
+            continue;
+        }
+
+        let words = words(base);
+        let delta_from = |word: u64, from: u64| sign_extend(word.wrapping_sub(from), base);
+        // A base é a primeira palavra que não cabe como delta de zero.
+        let explicit = words
+            .iter()
+            .copied()
+            .find(|&word| !fits(delta_from(word, 0), delta));
+        let compressible = words.iter().all(|&word| {
+            fits(delta_from(word, 0), delta)
+                || explicit.map_or(false, |explicit| fits(delta_from(word, explicit), delta))
+        });
+
+        if compressible {
+            // Base, deltas e um bit por palavra dizendo qual das duas bases.
+            best = best.min(base + words.len() * delta + (words.len() + 7) / 8);
+        }
+    }
+
+    best
+}
+
+fn fpc_size(line: &[u8]) -> usize {
+    if line.len() % 4 != 0 {
+        return line.len();
+    }
+
+    let bits: usize = line
+        .chunks(4)
+        .map(|chunk| {
+            let word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            let signed = i64::from(word as i32);
+            let halves = [word & 0xffff, word >> 16];
+            // Padrões do fpc: zero, com sinal em 4, 8 ou 16 bits, bytes
+            // repetidos, metade de baixo zerada e duas metades de 1 byte.
+            let payload = if word == 0 {
+                0
+            } else if (-8..8).contains(&signed) {
+                4
+            } else if fits(signed, 1) || chunk.iter().all(|&byte| byte == chunk[0]) {
+                8
+            } else if fits(signed, 2)
+                || word & 0xffff == 0
+                || halves
+                    .iter()
+                    .all(|&half| fits(sign_extend(u64::from(half), 2), 1))
+            {
+                16
+            } else {
+                32
+            };
+
+            3 + payload
+        })
+        .sum();
+
+    (bits + 7) / 8
+}
+
+// Bytes que a linha ocupa na cache comprimida, em segmentos inteiros e no
+// máximo o tamanho dela sem compressão.
+pub fn line_size(algorithm: Algorithm, line: &[u8]) -> usize {
+    let size = match algorithm {
+        Algorithm::Bdi => bdi_size(line),
+        Algorithm::Fpc => fpc_size(line),
+    };
+
+    ((size + SEGMENT - 1) / SEGMENT * SEGMENT).min(line.len())
+}
+
+// O conteúdo da memória visto nos valores do trace, em palavras de 8 bytes.
+#[derive(Debug, Default)]
+pub struct Memory {
+    words: std::collections::HashMap<u64, u64>,
+}
+
+impl Memory {
+    pub fn write(&mut self, adress: u64, size: usize, value: u64) {
+        for byte in 0..size.min(8) as u64 {
+            let adress = adress.wrapping_add(byte);
+            let shift = (adress & 7) * 8;
+            let word = self.words.entry(adress >> 3).or_insert(0);
+            *word = (*word & !(0xff << shift)) | (((value >> (byte * 8)) & 0xff) << shift);
+        }
+    }
+
+    fn byte(&self, adress: u64) -> u8 {
+        self.words
+            .get(&(adress >> 3))
+            .map(|word| (word >> ((adress & 7) * 8)) as u8)
+            .unwrap_or(0)
+    }
+}
+
+// splitmix64, para os dados sintéticos dependerem só do número do bloco.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl Values {
+    // Os `bsize` bytes do bloco.
+    pub fn line(&self, memory: &Memory, block: u64, bsize: usize) -> Vec<u8> {
+        let start = block.wrapping_mul(bsize as u64);
+        match *self {
+            Values::Trace => (0..bsize as u64)
+                .map(|byte| memory.byte(start.wrapping_add(byte)))
+                .collect(),
+            Values::Synthetic { zero, narrow } => {
+                let seed = mix(block);
+                let kind = (seed >> 11) as f64 / (1u64 << 53) as f64;
+                if kind < zero {
+                    return vec![0; bsize];
+                }
+
+                let base = mix(seed);
+                (0..(bsize as u64 + 7) / 8)
+                    .flat_map(|word| {
+                        let value = if kind < zero + narrow {
+                            base.wrapping_add(mix(seed ^ word) % 256).wrapping_sub(128)
+                        } else {
+                            mix(seed.wrapping_add(word + 1))
+                        };
+                        value.to_le_bytes().to_vec()
+                    })
+                    .take(bsize)
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Stats {
+    pub compression: Compression,
+    // Linhas trazidas para a cache fora do aquecimento e os bytes delas com e
+    // sem compressão.
+    pub fills: usize,
+    pub compressed_bytes: usize,
+    pub uncompressed_bytes: usize,
+    // Linhas na cache no fim da simulação.
+    pub resident_lines: usize,
+    // Hits da mesma cache sem compressão.
+    pub baseline_hits: usize,
+}
+
+impl Stats {
+    pub fn new(compression: Compression) -> Stats {
+        Stats {
+            compression,
+            fills: 0,
+            compressed_bytes: 0,
+            uncompressed_bytes: 0,
+            resident_lines: 0,
+            baseline_hits: 0,
+        }
+    }
+
+    // Bytes sem compressão por byte comprimido, 1 sem nenhuma linha trazida.
+    pub fn ratio(&self) -> f64 {
+        if self.compressed_bytes == 0 {
+            1.0
+        } else {
+            self.uncompressed_bytes as f64 / self.compressed_bytes as f64
+        }
+    }
+
+    pub fn extra_hits(&self, hits: usize) -> i64 {
+        hits as i64 - self.baseline_hits as i64
+    }
+
+    // `lines` é quantas linhas a cache guarda sem compressão.
+    pub fn format(&self, hits: usize, lines: usize) -> String {
+        format!(
+            concat!(
+                "compression: {}, {}\n",
+                "compression ratio   = {:.4}\n",
+                "resident lines      = {} (uncompressed capacity: {})\n",
+                "hits                = {} (uncompressed: {}, extra: {})"
+            ),
+            self.compression.algorithm,
+            match self.compression.values {
+                Values::Trace => "values from the trace".to_owned(),
+                Values::Synthetic { zero, narrow } => {
+                    format!("synthetic values ({} zero, {} narrow)", zero, narrow)
+                }
+            },
+            self.ratio(),
+            self.resident_lines,
+            lines,
+            hits,
+            self.baseline_hits,
+            self.extra_hits(hits)
+        )
+    }
+}
@@ -1,4 +1,20 @@
+pub mod analysis;
 pub mod cache;
+pub mod compression;
+pub mod energy;
+pub mod indexing;
+pub mod repeat;
+pub mod report;
+pub mod sampling;
+pub mod search;
+pub mod skewed;
+pub mod stacksim;
+pub mod stats;
+pub mod sweep;
+pub mod timeline;
+pub mod tlb;
+pub mod trace;
+pub mod waypred;
 
 pub trait TryPowerOfTwo {
     fn try_power_of_two(&self) -> Result<Self, Self>
@@ -21,29 +37,84 @@ pub enum Either<T1, T2> {
     Right(T2),
 }
 
+// Opções da simulação que não fazem parte da interface original do trabalho.
+#[derive(Debug, Default, Clone)]
+pub struct RunOptions {
+    // Guarda quanto os contadores andaram a cada `window` acessos do trace.
+    pub window: Option<usize>,
+    // Acessos do começo do trace que só aquecem a cache, sem contar.
+    pub warmup: Option<Warmup>,
+    pub sampling: Option<sampling::Sampling>,
+    // Seed da política Random, None usa uma aleatória.
+    pub seed: Option<u64>,
+    pub index: indexing::IndexFunction,
+    pub organisation: cache::Organisation,
+    // Tamanho do sub-bloco em bytes. Com ele cada bloco vira um setor com uma
+    // tag só e um bit de válido e sujo por sub-bloco.
+    pub sub_block: Option<usize>,
+    pub way_predictor: Option<waypred::Predictor>,
+    // Com ele o run_with estima a energia e a área da cache.
+    pub technology: Option<energy::Technology>,
+    pub compression: Option<compression::Compression>,
+    // Com ela a cache é indexada pelo endereço físico, depois da TLB.
+    pub translation: Option<tlb::Translation>,
+    // Refaz a simulação com o endereço virtual e com outra alocação de
+    // páginas para comparar os misses de conflito.
+    pub compare_translation: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Warmup {
+    // Quantidade de acessos do trace.
+    Accesses(usize),
+    // Fração do trace, entre 0 e 1.
+    Fraction(f64),
+}
+
+impl Warmup {
+    // Quantos acessos de um trace com `len` acessos são de aquecimento.
+    pub fn accesses(&self, len: usize) -> usize {
+        match *self {
+            Warmup::Accesses(accesses) => accesses.min(len),
+            Warmup::Fraction(fraction) => (len as f64 * fraction).round() as usize,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct RunParams {
     pub nsets: usize,
     pub bsize: usize,
     pub assoc: usize,
     pub repl: cache::ReplacementPolicy,
     pub verbosity: u8,
-    pub input: Vec<u32>,
+    // Quantos bits tem cada endereço da entrada (16, 32 ou 64).
+    pub adress_bits: usize,
+    // Compartilhado para que várias simulações (sweep) usem o mesmo trace.
+    pub input: std::sync::Arc<Vec<trace::Record>>,
+    pub options: RunOptions,
 }
 
 pub fn run_with(params: &RunParams) -> cache::Cache {
-    let makemask = |toggled_bits: usize, offset: usize| -> Result<u32, String> {
-        let tot_bits = std::mem::size_of::<u32>() * 8;
+    simulate(params, true)
+}
+
+// Com `baselines` false não refaz a simulação para comparar com a compressão
+// e com outras traduções, que é o caso dessas próprias simulações.
+fn simulate(params: &RunParams, baselines: bool) -> cache::Cache {
+    let makemask = |toggled_bits: usize, offset: usize| -> Result<u64, String> {
+        let tot_bits = std::mem::size_of::<u64>() * 8;
         match offset.cmp(&tot_bits) {
             std::cmp::Ordering::Greater => Err(format!(
-                "offset recebido foi {}, que é maior que std::mem::size_of::<u32>() * 8",
+                "offset recebido foi {}, que é maior que std::mem::size_of::<u64>() * 8",
                 offset
             )),
             std::cmp::Ordering::Equal => Ok(0),
             std::cmp::Ordering::Less => {
                 if toggled_bits >= tot_bits {
-                    Ok(u32::max_value() << offset)
+                    Ok(u64::MAX << offset)
                 } else {
-                    Ok((2u32.pow(toggled_bits as u32) - 1) << offset)
+                    Ok((2u64.pow(toggled_bits as u32) - 1) << offset)
                 }
             }
         }
@@ -59,56 +130,356 @@ pub fn run_with(params: &RunParams) -> cache::Cache {
         .map(|num| makemask(num.get(), nbits_offset).unwrap())
         .unwrap_or(0);
 
-    let nbits_instrucao = 32;
+    let nbits_instrucao = params.adress_bits;
     let nbits_tag = nbits_instrucao - nbits_index - nbits_offset;
     let tag_mask = std::num::NonZeroUsize::new(nbits_tag)
         .map(|num| makemask(num.get(), nbits_index + nbits_offset).unwrap())
         .unwrap_or(0);
 
+    // As máscaras só valem para IndexFunction::BitSlice, as outras funções
+    // usam o número do bloco inteiro.
+    let indexer = indexing::Indexer::new(&params.options.index, params.nsets, params.bsize);
+
+    // Precisa ser nbits_instrucao + 2 porque o '#' adiciona '0b' ao inicio.
+    let width = nbits_instrucao + 2;
     if params.verbosity != 1 {
         println!(
-            // Precisa ser com {:#034b} ao invez de 32 porque o '#' adiciona '0b' ao inicio.
-            "nbits_indice = {}\nindex_mask  = {:#034b}\nnbits_offset = {}\noffset_mask = {:#034b}\nnbits_tag = {}\ntag_mask    = {:#034b}",
-            nbits_index, index_mask, nbits_offset, offset_mask, nbits_tag, tag_mask
+            "nbits_indice = {}\nindex_mask  = {:#0w$b}\nnbits_offset = {}\noffset_mask = {:#0w$b}\nnbits_tag = {}\ntag_mask    = {:#0w$b}",
+            nbits_index, index_mask, nbits_offset, offset_mask, nbits_tag, tag_mask, w = width
         );
     }
 
-    let mut cache = cache::Cache::create(
-        params.nsets,
-        params.bsize,
-        params.repl,
-        params.assoc,
-        cache::Kind::Both,
-    );
+    let mut cache = match params.options.seed {
+        Some(seed) => cache::Cache::create_with_seed(
+            params.nsets,
+            params.bsize,
+            params.repl,
+            params.assoc,
+            cache::Kind::Both,
+            seed,
+        ),
+        None => cache::Cache::create(
+            params.nsets,
+            params.bsize,
+            params.repl,
+            params.assoc,
+            cache::Kind::Both,
+        ),
+    };
 
-    for (iteration, adress) in params.input.iter().enumerate() {
-        let offset = (adress & offset_mask) as usize;
+    cache.info.index = params.options.index.clone();
+    cache.info.organisation = params.options.organisation;
+    // Os conjuntos que o índice não alcança não contam no tamanho, senão a
+    // cache nunca fica cheia e os misses de capacidade viram de conflito.
+    let used_sets = sweep::Config::from(&cache.info).used_sets();
+    cache.info.total_slots = used_sets * params.assoc;
+    cache.info.size = used_sets * params.bsize * params.assoc;
+    cache.info.translation = params.options.translation;
+    if let Some(sub_block) = params.options.sub_block {
+        cache.info.sub_block = sub_block;
+    }
 
-        let unshifted_index = adress & index_mask;
-        let index = (unshifted_index >> nbits_offset) as usize;
+    // A compressão só vale para a organização normal, sem setores.
+    let compressed = params.options.compression.filter(|_| {
+        params.options.organisation == cache::Organisation::SetAssociative
+            && params.options.sub_block.is_none()
+    });
+    let mut memory = compression::Memory::default();
+    if let Some(compressed) = compressed {
+        cache.enable_compression();
+        cache.compression = Some(compression::Stats::new(compressed));
+        cache.info.compression = Some(compressed);
+    }
 
-        let unshifted_tag = adress & tag_mask;
-        let tag = (unshifted_tag >> (nbits_index + nbits_offset)) as usize;
+    let warmup = params
+        .options
+        .warmup
+        .map(|warmup| warmup.accesses(params.input.len()))
+        .unwrap_or(0);
+    cache.warming_up = warmup > 0;
 
-        let res = cache.access_with(index, tag, offset);
+    let mut window_start = cache.performance.clone();
+    let mut window_blocks = std::collections::HashSet::new();
 
-        if params.verbosity == 2 {
-            // Precisa ser com {:#034b} ao invez de 32 porque o '#' adiciona '0b' ao inicio.
-            println!("iteration = {}, ret = {:?}", iteration, res);
-            println!("adress = {0:#034b} {{{0}}}", adress);
-            println!("offset = {0:#034b} {{{0}}}", offset);
-            println!(
-                "index  = {0:#034b} ==lshift {1} bits==> {2:#034b} {{{2}}}",
-                unshifted_index, nbits_offset, index
-            );
-            println!(
-                "tag    = {0:#034b} ==lshift {1} bits==> {2:#034b} {{{2}}}",
-                unshifted_tag,
-                nbits_index + nbits_offset,
-                tag,
-            );
-            println!();
+    let mut sample_start = cache.performance.clone();
+
+    // A skewed não tem um conjunto por bloco para a previsão de via.
+    let mut way_predictor = params
+        .options
+        .way_predictor
+        .filter(|_| params.options.organisation == cache::Organisation::SetAssociative)
+        .map(|predictor| waypred::WayPredictor::new(predictor, params.nsets));
+    let mut pc = 0;
+
+    // Uma cor por página que cabe em uma via da cache.
+    let mut mmu = params.options.translation.map(|translation| {
+        let colours = (params.nsets * params.bsize / translation.page_size).max(1);
+        tlb::Mmu::new(translation, colours, nbits_instrucao, cache.info.seed)
+    });
+
+    for (iteration, record) in params.input.iter().enumerate() {
+        if iteration == warmup && cache.warming_up {
+            cache.end_warmup();
+        }
+        if let Some(value) = record.value {
+            memory.write(record.adress, record.size, value);
+        }
+        // O próprio fetch e os acessos a dados dele usam entradas diferentes
+        // da tabela do Predictor::Pc.
+        let pc_key = if record.access == trace::AccessType::Fetch {
+            pc = record.adress;
+            pc << 1 | 1
+        } else {
+            pc << 1
+        };
+
+        let phase = match params.options.sampling {
+            Some(sampling) if iteration >= warmup => sampling.phase(iteration - warmup),
+            _ => sampling::Phase::Simulate,
+        };
+        match phase {
+            sampling::Phase::Warmup => cache.warming_up = true,
+            sampling::Phase::Measure { first, .. } => {
+                cache.end_warmup();
+                if first {
+                    sample_start = cache.performance.clone();
+                }
+            }
+            _ => (),
+        }
+        let simulated = phase != sampling::Phase::Skip;
+
+        // Um acesso que passa do fim do bloco é dividido em um acesso para
+        // cada bloco tocado, o primeiro começando no próprio endereço e os
+        // outros no inicio do bloco.
+        let blocks = record.blocks(nbits_offset);
+        let first_block = *blocks.start();
+        if simulated && *blocks.end() != first_block {
+            cache.counters_mut().split_accesses += 1;
+        }
+
+        for virtual_block in blocks.filter(|_| simulated) {
+            let adress = if virtual_block == first_block {
+                record.adress
+            } else {
+                virtual_block << nbits_offset
+            };
+            // O bloco não passa do fim da página, que é pelo menos do tamanho
+            // dele.
+            let adress = match &mut mmu {
+                Some(mmu) => mmu.translate(adress, !cache.warming_up),
+                None => adress,
+            };
+            let block = adress >> nbits_offset;
+
+            let offset = (adress & offset_mask) as usize;
+
+            let unshifted_index = adress & index_mask;
+            let unshifted_tag = adress & tag_mask;
+            let (index, tag) = match &params.options.index {
+                indexing::IndexFunction::BitSlice => (
+                    (unshifted_index >> nbits_offset) as usize,
+                    (unshifted_tag >> (nbits_index + nbits_offset)) as usize,
+                ),
+                _ => indexer.split(block),
+            };
+
+            if let Some(sampling) = params.options.sampling {
+                if !sampling.keeps_set(index) {
+                    continue;
+                }
+            }
+
+            let hit_way = way_predictor
+                .as_ref()
+                .and_then(|_| cache.way_of(index, tag));
+            let counted = !cache.warming_up;
+
+            let res = match (params.options.organisation, params.options.sub_block) {
+                // Cada sub-bloco tocado dentro do setor conta como um acesso.
+                (cache::Organisation::SetAssociative, Some(sub_block)) => {
+                    let last_byte = adress
+                        .saturating_add(record.size.max(1) as u64 - 1)
+                        .min(block << nbits_offset | offset_mask);
+                    let last_offset = (last_byte & offset_mask) as usize;
+                    let write = record.access == trace::AccessType::Write;
+
+                    let mut res = cache::AccessResult::Hit;
+                    for sub in offset / sub_block..=last_offset / sub_block {
+                        res = cache.access_sectored(index, tag, sub, write);
+                    }
+                    res
+                }
+                (cache::Organisation::SetAssociative, None) => match compressed {
+                    Some(compressed) => {
+                        let line = compressed.values.line(&memory, virtual_block, params.bsize);
+                        let size = compression::line_size(compressed.algorithm, &line);
+                        let res = cache.access_compressed(index, tag, size);
+
+                        if let (Some(stats), cache::AccessResult::Miss(_), true) =
+                            (&mut cache.compression, &res, counted)
+                        {
+                            stats.fills += 1;
+                            stats.compressed_bytes += size;
+                            stats.uncompressed_bytes += params.bsize;
+                        }
+                        res
+                    }
+                    None => cache.access_with(index, tag, offset),
+                },
+                (cache::Organisation::Skewed, _) => cache.access_skewed(
+                    &skewed::indexes(block, params.nsets, params.assoc),
+                    block as usize,
+                ),
+            };
+
+            if let Some(predictor) = &mut way_predictor {
+                let way = cache.way_of(index, tag).unwrap();
+                predictor.record(index, pc_key, hit_way, way, counted);
+            }
+
+            if params.verbosity == 2 {
+                println!("iteration = {}, ret = {:?}", iteration, res);
+                println!("adress = {0:#0w$b} {{{0}}}", adress, w = width);
+                println!("offset = {0:#0w$b} {{{0}}}", offset, w = width);
+                if params.options.index == indexing::IndexFunction::BitSlice {
+                    println!(
+                        "index  = {0:#0w$b} ==lshift {1} bits==> {2:#0w$b} {{{2}}}",
+                        unshifted_index,
+                        nbits_offset,
+                        index,
+                        w = width
+                    );
+                    println!(
+                        "tag    = {0:#0w$b} ==lshift {1} bits==> {2:#0w$b} {{{2}}}",
+                        unshifted_tag,
+                        nbits_index + nbits_offset,
+                        tag,
+                        w = width
+                    );
+                } else {
+                    println!("index  = {}(block {}) = {}", indexer.function, block, index);
+                    println!("tag    = {}", tag);
+                }
+                println!();
+            }
         }
+
+        if let sampling::Phase::Measure { last, .. } = phase {
+            if last || iteration + 1 == params.input.len() {
+                cache.samples.push(cache.performance.since(&sample_start));
+            }
+        }
+
+        if let Some(window) = params.options.window {
+            window_blocks.extend(record.blocks(nbits_offset));
+
+            let length = iteration % window + 1;
+            if length == window || iteration + 1 == params.input.len() {
+                cache.windows.push(timeline::Window {
+                    start: iteration + 1 - length,
+                    length,
+                    delta: cache.performance.since(&window_start),
+                    blocks: window_blocks.len(),
+                });
+                window_start = cache.performance.clone();
+                window_blocks.clear();
+            }
+        }
+    }
+
+    if params.options.sub_block.is_some() {
+        cache.flush_sectors();
+    }
+    cache.way_predictor = way_predictor;
+
+    if compressed.is_some() && baselines {
+        // Os hits ganhos são contra a mesma simulação sem compressão.
+        let baseline = simulate(
+            &RunParams {
+                verbosity: 1,
+                options: RunOptions {
+                    compression: None,
+                    seed: Some(cache.info.seed),
+                    ..params.options.clone()
+                },
+                ..params.clone()
+            },
+            false,
+        );
+        let resident_lines = cache
+            .data
+            .iter()
+            .flatten()
+            .filter(|line| line.is_initialized)
+            .count();
+
+        let stats = cache.compression.as_mut().unwrap();
+        stats.baseline_hits = baseline.performance.hits;
+        stats.resident_lines = resident_lines;
+    }
+
+    if let (Some(mmu), true) = (&mmu, baselines) {
+        let mut stats = mmu.stats();
+
+        if params.options.compare_translation {
+            // O efeito das cores nos conflitos é contra a mesma simulação com
+            // o endereço virtual e com páginas coloridas (ou aleatórias, se
+            // essa já usou cores).
+            let conflicts = |translation| {
+                simulate(
+                    &RunParams {
+                        verbosity: 1,
+                        options: RunOptions {
+                            translation,
+                            seed: Some(cache.info.seed),
+                            technology: None,
+                            ..params.options.clone()
+                        },
+                        ..params.clone()
+                    },
+                    false,
+                )
+                .performance
+                .conflict_misses
+            };
+            let allocation = match mmu.translation.allocation {
+                tlb::Allocation::Coloured => tlb::Allocation::Random,
+                _ => tlb::Allocation::Coloured,
+            };
+
+            stats.comparison = Some(tlb::Comparison {
+                virtual_conflicts: conflicts(None),
+                allocation,
+                conflicts: conflicts(Some(tlb::Translation {
+                    allocation,
+                    ..mmu.translation
+                })),
+            });
+        }
+        cache.translation = Some(stats);
+    }
+
+    if let Some(technology) = &params.options.technology {
+        // A tag guardada é o bloco inteiro quando o índice não sai de bits
+        // fixos do endereço.
+        let tag_bits = match (&params.options.organisation, &params.options.index) {
+            (cache::Organisation::Skewed, _)
+            | (_, indexing::IndexFunction::Matrix(_))
+            | (_, indexing::IndexFunction::Select(_)) => nbits_instrucao - nbits_offset,
+            _ => nbits_tag,
+        };
+        let way_probes = cache
+            .way_predictor
+            .as_ref()
+            .map(|predictor| predictor.probes(params.assoc));
+        cache.energy = Some(energy::Estimate::compute(
+            technology,
+            &cache.info,
+            tag_bits,
+            way_probes,
+            &cache.performance,
+        ));
     }
 
     cache
@@ -135,37 +506,35 @@ fn power_of_two_error(field_name: &str, value: usize) -> String {
     )
 }
 
-fn readfile(filename: &str) -> Result<Vec<u32>, String> {
-    let raw_data: Vec<u8> = std::fs::read(filename).map_err(|e| format!("{:#?}", e))?;
-    if raw_data.len() % std::mem::size_of::<u32>() != 0 {
-        Err("Input file has wrong byte alignment".to_owned()
-            + "(cannot convert from Vec<u8> to Vec<u32> without clipping)")?
+fn parse_repl(repl: &str) -> cache::ReplacementPolicy {
+    match repl.to_ascii_lowercase().as_ref() {
+        "l" | "lru" => cache::ReplacementPolicy::Lru,
+        "f" | "fifo" => cache::ReplacementPolicy::Fifo,
+        "r" | "random" => cache::ReplacementPolicy::Random,
+        // Esse caso não deveria acontecer, no cli.yml tem os
+        // possíveis valores para esse argumento.
+        _ => panic!(),
     }
+}
 
-    let final_data: Vec<u32> = unsafe {
-        #[allow(clippy::cast_ptr_alignment)]
-        let temp = Vec::from_raw_parts(
-            raw_data.as_ptr() as *mut u32,
-            raw_data.len() / std::mem::size_of::<u32>(),
-            raw_data.capacity() / std::mem::size_of::<u32>(),
-        )
-        .into_iter()
-        .map(u32::from_be)
-        .collect();
-        std::mem::forget(raw_data);
-        temp
+// Tamanho em bytes com unidade opcional: K, KB e KiB são 1024 bytes e M, MB
+// e MiB são 1024 * 1024, como nas especificações de cache.
+pub fn parse_size(field_name: &str, size: &str) -> Result<usize, String> {
+    let digits = size.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let multiplier = match size[digits.len()..].to_ascii_lowercase().as_ref() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        _ => Err(format!(
+            "Malformed argument <{}>: unknown unit in '{}', use K, KiB, M or MiB",
+            field_name, size
+        ))?,
     };
 
-    Ok(final_data)
-}
-
-#[test]
-#[ignore]
-// Só deve rodar se os arquivos estiverem presente.
-fn readfile_test() {
-    readfile("testfiles/bin_100.bin").unwrap();
-    readfile("testfiles/bin_1000.bin").unwrap();
-    readfile("testfiles/bin_10000.bin").unwrap();
+    str::parse::<usize>(digits)
+        .ok()
+        .and_then(|digits| digits.checked_mul(multiplier))
+        .ok_or_else(|| conversion_error(field_name, size, "a size in bytes"))
 }
 
 pub fn parse_and_validate(
@@ -174,36 +543,68 @@ pub fn parse_and_validate(
     assoc: &str,
     repl: &str,
     verbosity: &str,
-    // Ou uma string com o caminho para o arquivo
+    // Ou uma tupla com o caminho para o arquivo e o formato dele
     // ou uma tupla com o tamanho do vetor e a seed.
-    input: Either<&str, (&str, String)>,
+    input: Either<(&str, trace::Format), (&str, String)>,
 ) -> Result<RunParams, String> {
-    let nsets = str::parse::<usize>(nsets)
-        .map_err(|_| conversion_error("nsets", nsets, "usize"))?
-        .try_power_of_two()
-        .map_err(|num| power_of_two_error("nsets", num))?;
     let bsize = str::parse::<usize>(bsize)
         .map_err(|_| conversion_error("bsize", bsize, "usize"))?
         .try_power_of_two()
         .map_err(|num| power_of_two_error("bsize", num))?;
-    let assoc = str::parse::<usize>(assoc)
-        .map_err(|_| conversion_error("assoc", assoc, "usize"))?
-        .try_power_of_two()
-        .map_err(|num| power_of_two_error("assoc", num))?;
+    // None é assoc "full", as linhas viram as vias de um conjunto só.
+    let assoc = if assoc.eq_ignore_ascii_case("full") {
+        None
+    } else {
+        let assoc =
+            str::parse::<usize>(assoc).map_err(|_| conversion_error("assoc", assoc, "usize"))?;
+        if assoc == 0 {
+            Err("Malformed argument <assoc>: sets need at least 1 way".to_owned())?
+        }
+        Some(assoc)
+    };
 
-    let repl = match repl.to_ascii_lowercase().as_ref() {
-        "l" | "lru" => cache::ReplacementPolicy::Lru,
-        "f" | "fifo" => cache::ReplacementPolicy::Fifo,
-        "r" | "random" => cache::ReplacementPolicy::Random,
-        // Esse caso não deveria acontecer, no cli.yml tem os
-        // possíveis valores para esse argumento.
-        _ => panic!(),
+    // Qualquer quantidade de conjuntos, as que não são potência de 2 usam
+    // IndexFunction::Modulo (ver parse_index). Com unidade (32KiB) nsets é o
+    // tamanho total da cache e a quantidade de conjuntos sai dele. A unidade
+    // tem que ser K ou M: 4096B ficaria parecido demais com 4096 conjuntos.
+    let nsets = if nsets.ends_with(|c: char| c.is_ascii_alphabetic()) {
+        let total_size_error =
+            |error: String| format!("{} (<nsets> with a unit is the total cache size)", error);
+        let unit = nsets.trim_start_matches(|c: char| c.is_ascii_digit());
+        if unit.eq_ignore_ascii_case("b") {
+            Err(total_size_error(format!(
+                "Malformed argument <nsets>: '{}' is a size in bytes, use K or M",
+                nsets
+            )))?
+        }
+        let size = parse_size("nsets", nsets).map_err(total_size_error)?;
+        let set_size = bsize * assoc.unwrap_or(1);
+        if size == 0 || size % set_size != 0 {
+            Err(total_size_error(format!(
+                "Malformed argument <nsets>: {} ({} bytes) cannot be split in sets of {} bytes (bsize * assoc)",
+                nsets, size, set_size
+            )))?
+        }
+        size / set_size
+    } else {
+        str::parse::<usize>(nsets).map_err(|_| conversion_error("nsets", nsets, "usize"))?
+    };
+    if nsets == 0 {
+        Err("Malformed argument <nsets>: the cache needs at least 1 set".to_owned())?
+    }
+    let (nsets, assoc) = match assoc {
+        Some(assoc) => (nsets, assoc),
+        None => (1, nsets),
     };
+
+    let repl = parse_repl(repl);
     let verbosity =
         str::parse::<u8>(verbosity).map_err(|_| conversion_error("verbosity", verbosity, "u8"))?;
 
-    let input = match input {
-        Either::Left(input_file) => readfile(input_file)?,
+    let (input, adress_bits) = match input {
+        Either::Left((input_file, format)) => {
+            (trace::readfile(input_file, &format)?, format.width.bits())
+        }
         Either::Right((vecsize, seed)) => {
             use rand::Rng;
 
@@ -213,21 +614,679 @@ pub fn parse_and_validate(
                 .map_err(|_| conversion_error("seed", seed.as_ref(), "u64"))?;
 
             let mut rng: rand::rngs::StdRng = rand::SeedableRng::seed_from_u64(seed);
-            let mut vec: Vec<u32> = Vec::with_capacity(vecsize);
+            let mut vec: Vec<trace::Record> = Vec::with_capacity(vecsize);
             for _ in 0..vecsize {
-                vec.push(rng.gen())
+                vec.push(trace::Record {
+                    adress: u64::from(rng.gen::<u32>()),
+                    size: 1,
+                    access: trace::AccessType::Read,
+                    value: None,
+                })
             }
 
-            vec
+            (vec, 32)
         }
     };
 
+    if log_2(nsets.next_power_of_two()) + log_2(bsize) > adress_bits {
+        Err(format!(
+            "Malformed arguments <nsets> and <bsize>: {} sets of {} bytes do not fit in {}-bit adresses",
+            nsets, bsize, adress_bits
+        ))?
+    }
+
     Ok(RunParams {
         nsets,
         bsize,
         assoc,
         repl,
         verbosity,
-        input,
+        adress_bits,
+        input: std::sync::Arc::new(input),
+        options: RunOptions {
+            index: indexing::IndexFunction::default_for(nsets),
+            ..Default::default()
+        },
+    })
+}
+
+pub fn parse_format(
+    encoding: &str,
+    endianness: &str,
+    width: &str,
+    trailing: &str,
+    access_size: &str,
+) -> Result<trace::Format, String> {
+    // Os valores possíveis para esses argumentos estão no cli.yml.
+    let encoding = match encoding.to_ascii_lowercase().as_ref() {
+        "bin" | "binary" => trace::Encoding::Binary,
+        "text" => trace::Encoding::Text,
+        "din" => trace::Encoding::Din,
+        "compact" => trace::Encoding::Compact,
+        _ => panic!(),
+    };
+    let endianness = match endianness.to_ascii_lowercase().as_ref() {
+        "b" | "big" => trace::Endianness::Big,
+        "l" | "little" => trace::Endianness::Little,
+        _ => panic!(),
+    };
+    let width =
+        match str::parse::<usize>(width).map_err(|_| conversion_error("width", width, "usize"))? {
+            16 => trace::RecordWidth::W16,
+            32 => trace::RecordWidth::W32,
+            64 => trace::RecordWidth::W64,
+            other => Err(format!(
+                "Malformed argument <width>: '{}' is not one of 16, 32 or 64",
+                other
+            ))?,
+        };
+    let trailing = match trailing.to_ascii_lowercase().as_ref() {
+        "reject" => trace::TrailingBytes::Reject,
+        "truncate" => trace::TrailingBytes::Truncate,
+        _ => panic!(),
+    };
+    let access_size = str::parse::<usize>(access_size)
+        .map_err(|_| conversion_error("access-size", access_size, "usize"))?;
+    if access_size == 0 {
+        Err("Malformed argument <access-size>: accesses must be at least 1 byte".to_owned())?
+    }
+
+    Ok(trace::Format {
+        encoding,
+        endianness,
+        width,
+        trailing,
+        access_size,
+    })
+}
+
+fn parse_adress(field_name: &str, value: &str) -> Result<u64, String> {
+    let parsed = if value.starts_with("0x") || value.starts_with("0X") {
+        u64::from_str_radix(&value[2..], 16)
+    } else {
+        str::parse::<u64>(value)
+    };
+
+    parsed.map_err(|_| conversion_error(field_name, value, "u64"))
+}
+
+pub fn parse_filter(
+    // Nenhum tipo deixa passar todos.
+    accesses: &[&str],
+    min_adress: Option<&str>,
+    max_adress: Option<&str>,
+) -> Result<trace::Filter, String> {
+    let mut filter = trace::Filter::default();
+
+    for access in accesses {
+        // Os valores possíveis para esse argumento estão no cli.yml.
+        filter
+            .accesses
+            .push(match access.to_ascii_lowercase().as_ref() {
+                "r" | "read" => trace::AccessType::Read,
+                "w" | "write" => trace::AccessType::Write,
+                "f" | "fetch" => trace::AccessType::Fetch,
+                _ => panic!(),
+            });
+    }
+    if let Some(min_adress) = min_adress {
+        filter.min_adress = parse_adress("min-adress", min_adress)?;
+    }
+    if let Some(max_adress) = max_adress {
+        filter.max_adress = parse_adress("max-adress", max_adress)?;
+    }
+    if filter.min_adress > filter.max_adress {
+        Err(format!(
+            "Malformed arguments <min-adress> and <max-adress>: {:#x} is greater than {:#x}",
+            filter.min_adress, filter.max_adress
+        ))?
+    }
+
+    Ok(filter)
+}
+
+// Cada valor pode ser um número ou uma faixa "a..b", que vira todas as
+// potências de 2 de a até b (inclusive).
+fn parse_power_of_two_list(field_name: &str, values: &[&str]) -> Result<Vec<usize>, String> {
+    let parse_one = |value: &str| -> Result<usize, String> {
+        str::parse::<usize>(value)
+            .map_err(|_| conversion_error(field_name, value, "usize"))?
+            .try_power_of_two()
+            .map_err(|num| power_of_two_error(field_name, num))
+    };
+
+    let mut list = Vec::new();
+    for value in values {
+        let mut bounds = value.splitn(2, "..");
+        let low = parse_one(bounds.next().unwrap())?;
+        match bounds.next() {
+            Some(high) => {
+                let high = parse_one(high)?;
+                if low > high {
+                    Err(format!(
+                        "Malformed argument <{}>: range '{}' is empty",
+                        field_name, value
+                    ))?
+                }
+                // Sem passar do maior usize quando high é a maior potência.
+                let mut current = Some(low);
+                while let Some(value) = current.filter(|&value| value <= high) {
+                    list.push(value);
+                    current = value.checked_mul(2);
+                }
+            }
+            None => list.push(low),
+        }
+    }
+
+    Ok(list)
+}
+
+pub fn parse_stats_options(bsizes: &[&str], window: &str) -> Result<(Vec<usize>, usize), String> {
+    let bsizes = parse_power_of_two_list("bsizes", bsizes)?;
+    let window =
+        str::parse::<usize>(window).map_err(|_| conversion_error("window", window, "usize"))?;
+    if window == 0 {
+        Err("Malformed argument <window>: windows must have at least 1 access".to_owned())?
+    }
+
+    Ok((bsizes, window))
+}
+
+pub fn parse_bsize(bsize: &str) -> Result<usize, String> {
+    str::parse::<usize>(bsize)
+        .map_err(|_| conversion_error("bsize", bsize, "usize"))?
+        .try_power_of_two()
+        .map_err(|num| power_of_two_error("bsize", num))
+}
+
+pub fn parse_grid_options(
+    bsize: &str,
+    nsets: &[&str],
+    assocs: &[&str],
+) -> Result<(usize, Vec<usize>, Vec<usize>), String> {
+    Ok((
+        parse_bsize(bsize)?,
+        parse_power_of_two_list("nsets", nsets)?,
+        parse_power_of_two_list("assoc", assocs)?,
+    ))
+}
+
+pub fn parse_sweep_options(
+    nsets: &[&str],
+    bsizes: &[&str],
+    assocs: &[&str],
+    repls: &[&str],
+    jobs: Option<&str>,
+    adress_bits: usize,
+) -> Result<(Vec<sweep::Config>, usize), String> {
+    let nsets = parse_power_of_two_list("nsets", nsets)?;
+    let bsizes = parse_power_of_two_list("bsize", bsizes)?;
+    let assocs = parse_power_of_two_list("assoc", assocs)?;
+    let repls: Vec<cache::ReplacementPolicy> = repls.iter().map(|repl| parse_repl(repl)).collect();
+
+    let jobs = match jobs {
+        Some(jobs) => {
+            str::parse::<usize>(jobs).map_err(|_| conversion_error("jobs", jobs, "usize"))?
+        }
+        None => std::thread::available_parallelism()
+            .map(|jobs| jobs.get())
+            .unwrap_or(1),
+    };
+    if jobs == 0 {
+        Err("Malformed argument <jobs>: at least 1 worker thread is needed".to_owned())?
+    }
+
+    let mut configs = sweep::configurations(&nsets, &bsizes, &assocs, &repls);
+    configs.retain(|config| {
+        let fits = log_2(config.nsets) + log_2(config.bsize) <= adress_bits;
+        if !fits {
+            eprintln!(
+                "Warning: skipping {} sets of {} bytes, they do not fit in {}-bit adresses",
+                config.nsets, config.bsize, adress_bits
+            );
+        }
+        fits
+    });
+
+    Ok((configs, jobs))
+}
+
+pub fn parse_search_options(
+    budget: Option<&str>,
+    area: bool,
+    latency: Option<&[&str]>,
+    adress_bits: usize,
+) -> Result<Option<search::Search>, String> {
+    // Com unidade opcional, como o tamanho total no lugar de nsets.
+    let budget = match budget {
+        Some(budget) => parse_size("budget", budget)?,
+        None => return Ok(None),
+    };
+
+    let latency = match latency {
+        Some(coefficients) => {
+            let coefficients = coefficients
+                .iter()
+                .map(|value| {
+                    str::parse::<f64>(value)
+                        .map_err(|_| conversion_error("latency-model", value, "f64"))
+                })
+                .collect::<Result<Vec<f64>, String>>()?;
+            if coefficients.len() != 3 {
+                Err(format!(
+                    "Malformed argument <latency-model>: expected 3 coefficients but got {}",
+                    coefficients.len()
+                ))?
+            }
+
+            Some(search::LatencyModel {
+                base: coefficients[0],
+                per_size_doubling: coefficients[1],
+                per_assoc_doubling: coefficients[2],
+            })
+        }
+        None => None,
+    };
+
+    Ok(Some(search::Search {
+        budget,
+        area,
+        latency,
+        adress_bits,
+    }))
+}
+
+pub fn parse_output(output: &str) -> report::Output {
+    // Os valores possíveis para esse argumento estão no cli.yml.
+    match output.to_ascii_lowercase().as_ref() {
+        "text" => report::Output::Text,
+        "json" => report::Output::Json,
+        "csv" => report::Output::Csv,
+        _ => panic!(),
+    }
+}
+
+pub fn parse_set_report(hottest: &str) -> Result<usize, String> {
+    str::parse::<usize>(hottest).map_err(|_| conversion_error("hottest", hottest, "usize"))
+}
+
+pub fn parse_window_options(
+    window: &str,
+    miss_rate_threshold: &str,
+    working_set_threshold: &str,
+) -> Result<(usize, timeline::PhaseDetector), String> {
+    let window =
+        str::parse::<usize>(window).map_err(|_| conversion_error("window", window, "usize"))?;
+    if window == 0 {
+        Err("Malformed argument <window>: windows must have at least 1 access".to_owned())?
+    }
+    let miss_rate_threshold = str::parse::<f64>(miss_rate_threshold)
+        .map_err(|_| conversion_error("phase-threshold", miss_rate_threshold, "f64"))?;
+    let working_set_threshold = str::parse::<f64>(working_set_threshold)
+        .map_err(|_| conversion_error("ws-threshold", working_set_threshold, "f64"))?;
+
+    Ok((
+        window,
+        timeline::PhaseDetector {
+            miss_rate_threshold,
+            working_set_threshold,
+        },
+    ))
+}
+
+// Um número de acessos ("5000"), uma porcentagem ("10%") ou uma fração ("0.1")
+// do trace.
+pub fn parse_warmup(warmup: &str) -> Result<Warmup, String> {
+    let fraction = if let Some(percent) = warmup.strip_suffix('%') {
+        str::parse::<f64>(percent).map_err(|_| conversion_error("warmup", warmup, "f64"))? / 100.0
+    } else if warmup.contains('.') {
+        str::parse::<f64>(warmup).map_err(|_| conversion_error("warmup", warmup, "f64"))?
+    } else {
+        return str::parse::<usize>(warmup)
+            .map(Warmup::Accesses)
+            .map_err(|_| conversion_error("warmup", warmup, "usize"));
+    };
+
+    if !(0.0..=1.0).contains(&fraction) {
+        Err(format!(
+            "Malformed argument <warmup>: '{}' is not between 0% and 100% of the trace",
+            warmup
+        ))?
+    }
+
+    Ok(Warmup::Fraction(fraction))
+}
+
+pub fn parse_sampling(
+    sets: Option<&str>,
+    time: Option<&[&str]>,
+) -> Result<Option<sampling::Sampling>, String> {
+    if let Some(every) = sets {
+        let every = str::parse::<usize>(every)
+            .map_err(|_| conversion_error("sample-sets", every, "usize"))?;
+        if every == 0 {
+            Err("Malformed argument <sample-sets>: at least 1 set must be simulated".to_owned())?
+        }
+
+        return Ok(Some(sampling::Sampling::Sets { every }));
+    }
+
+    let values = match time {
+        Some(values) => values
+            .iter()
+            .map(|value| {
+                str::parse::<usize>(value)
+                    .map_err(|_| conversion_error("sample-time", value, "usize"))
+            })
+            .collect::<Result<Vec<usize>, String>>()?,
+        None => return Ok(None),
+    };
+    if values.len() != 3 {
+        Err(format!(
+            "Malformed argument <sample-time>: expected 'period,warmup,length' but got {} values",
+            values.len()
+        ))?
+    }
+    let (period, warmup, length) = (values[0], values[1], values[2]);
+    if length == 0 || warmup + length > period {
+        Err(format!(
+            "Malformed argument <sample-time>: {} warm-up and {} measured accesses do not fit in a period of {}",
+            warmup, length, period
+        ))?
+    }
+
+    Ok(Some(sampling::Sampling::Time {
+        period,
+        warmup,
+        length,
+    }))
+}
+
+pub fn parse_repl_seed(seed: &str) -> Result<u64, String> {
+    str::parse::<u64>(seed).map_err(|_| conversion_error("repl-seed", seed, "u64"))
+}
+
+pub fn parse_repeat(repeat: &str) -> Result<usize, String> {
+    let repeat =
+        str::parse::<usize>(repeat).map_err(|_| conversion_error("repeat", repeat, "usize"))?;
+    if repeat < 2 {
+        Err(
+            "Malformed argument <repeat>: at least 2 seeds are needed for a confidence interval"
+                .to_owned(),
+        )?
+    }
+
+    Ok(repeat)
+}
+
+pub fn parse_index(
+    index: &str,
+    // Linhas da matriz, só com index "matrix".
+    matrix: Option<&[&str]>,
+    // Bits do endereço, só com index "select".
+    select: Option<&[&str]>,
+    nsets: usize,
+    bsize: usize,
+) -> Result<indexing::IndexFunction, String> {
+    let nbits_offset = log_2(bsize);
+
+    // Os valores possíveis para esse argumento estão no cli.yml.
+    let index = match index.to_ascii_lowercase().as_ref() {
+        "bits" => indexing::IndexFunction::BitSlice,
+        "modulo" => indexing::IndexFunction::Modulo,
+        "prime" => indexing::IndexFunction::PrimeModulo,
+        "xor" => indexing::IndexFunction::XorFold,
+        "matrix" => {
+            let rows = matrix
+                .ok_or("Missing argument <index-matrix>: --index matrix needs the matrix rows")?
+                .iter()
+                .map(|&row| parse_adress("index-matrix", row))
+                .collect::<Result<Vec<u64>, String>>()?;
+            if let Some(row) = rows
+                .iter()
+                .find(|&&row| row & ((1 << nbits_offset) - 1) != 0)
+            {
+                Err(format!(
+                    "Malformed argument <index-matrix>: row {:#x} uses offset bits of {}-byte blocks",
+                    row, bsize
+                ))?
+            }
+            indexing::IndexFunction::Matrix(rows)
+        }
+        "select" => {
+            let bits = select
+                .ok_or("Missing argument <index-bits>: --index select needs the adress bits")?
+                .iter()
+                .map(|&bit| {
+                    str::parse::<u32>(bit).map_err(|_| conversion_error("index-bits", bit, "u32"))
+                })
+                .collect::<Result<Vec<u32>, String>>()?;
+            if let Some(bit) = bits
+                .iter()
+                .find(|&&bit| (bit as usize) < nbits_offset || bit >= 64)
+            {
+                Err(format!(
+                    "Malformed argument <index-bits>: bit {} is not a block bit of a 64-bit adress with {}-byte blocks",
+                    bit, bsize
+                ))?
+            }
+            indexing::IndexFunction::Select(bits)
+        }
+        _ => panic!(),
+    };
+
+    // Depois do teste de potência de 2 as linhas e os bits são comparados com
+    // log2(nsets), 1 << len estouraria com 64 ou mais.
+    match &index {
+        indexing::IndexFunction::Modulo | indexing::IndexFunction::PrimeModulo => (),
+        _ if nsets.try_power_of_two().is_err() => Err(format!(
+            "{}, use --index modulo or --index prime",
+            power_of_two_error("nsets", nsets)
+        ))?,
+        indexing::IndexFunction::Matrix(rows) if rows.len() != log_2(nsets) => Err(format!(
+            "Malformed argument <index-matrix>: {} sets need {} rows but got {}",
+            nsets,
+            log_2(nsets),
+            rows.len()
+        ))?,
+        indexing::IndexFunction::Select(bits) if bits.len() != log_2(nsets) => Err(format!(
+            "Malformed argument <index-bits>: {} sets need {} bits but got {}",
+            nsets,
+            log_2(nsets),
+            bits.len()
+        ))?,
+        _ => (),
+    }
+
+    Ok(index)
+}
+
+pub fn parse_organisation(organisation: &str, nsets: usize) -> Result<cache::Organisation, String> {
+    // Os valores possíveis para esse argumento estão no cli.yml.
+    let organisation = match organisation.to_ascii_lowercase().as_ref() {
+        "set-associative" => cache::Organisation::SetAssociative,
+        "skewed" => cache::Organisation::Skewed,
+        _ => panic!(),
+    };
+    if organisation == cache::Organisation::Skewed && nsets.try_power_of_two().is_err() {
+        Err(format!(
+            "{}, skewed caches index each way with a bit function",
+            power_of_two_error("nsets", nsets)
+        ))?
+    }
+
+    Ok(organisation)
+}
+
+pub fn parse_sub_block(sub_block: &str, bsize: usize) -> Result<usize, String> {
+    let sub_block = str::parse::<usize>(sub_block)
+        .map_err(|_| conversion_error("sub-block", sub_block, "usize"))?
+        .try_power_of_two()
+        .map_err(|sub_block| power_of_two_error("sub-block", sub_block))?;
+    if sub_block > bsize {
+        Err(format!(
+            "Malformed argument <sub-block>: '{}' is larger than the block size ({})",
+            sub_block, bsize
+        ))?
+    }
+    // Os bits de válido e sujo de cada setor ficam num u64.
+    if bsize / sub_block > 64 {
+        Err(format!(
+            "Malformed argument <sub-block>: '{}' splits each block in {} sub-blocks, at most 64 are supported",
+            sub_block,
+            bsize / sub_block
+        ))?
+    }
+
+    Ok(sub_block)
+}
+
+pub fn parse_way_predictor(
+    predictor: &str,
+    entries: Option<&str>,
+) -> Result<waypred::Predictor, String> {
+    // Os valores possíveis para esse argumento estão no cli.yml.
+    match predictor.to_ascii_lowercase().as_ref() {
+        "mru" => Ok(waypred::Predictor::Mru),
+        "pc" => {
+            let entries = match entries {
+                Some(entries) => str::parse::<usize>(entries)
+                    .map_err(|_| conversion_error("predictor-entries", entries, "usize"))?,
+                None => 1024,
+            };
+            if entries == 0 {
+                Err(
+                    "Malformed argument <predictor-entries>: the table needs at least 1 entry"
+                        .to_owned(),
+                )?
+            }
+            Ok(waypred::Predictor::Pc { entries })
+        }
+        _ => panic!(),
+    }
+}
+
+pub fn parse_compression(
+    algorithm: &str,
+    values: Option<&str>,
+) -> Result<compression::Compression, String> {
+    // Os valores possíveis para esse argumento estão no cli.yml.
+    let algorithm = match algorithm.to_ascii_lowercase().as_ref() {
+        "bdi" => compression::Algorithm::Bdi,
+        "fpc" => compression::Algorithm::Fpc,
+        _ => panic!(),
+    };
+
+    let values = match values {
+        Some(values) => {
+            let fractions = values
+                .split(',')
+                .map(|fraction| {
+                    str::parse::<f64>(fraction.trim())
+                        .ok()
+                        .filter(|fraction| (0.0..=1.0).contains(fraction))
+                        .ok_or_else(|| conversion_error("value-model", fraction, "a fraction"))
+                })
+                .collect::<Result<Vec<f64>, String>>()?;
+            if fractions.len() != 2 || fractions[0] + fractions[1] > 1.0 {
+                Err(format!(
+                    "Malformed argument <value-model>: expected 'zero,narrow' fractions adding up to at most 1 but got '{}'",
+                    values
+                ))?
+            }
+            compression::Values::Synthetic {
+                zero: fractions[0],
+                narrow: fractions[1],
+            }
+        }
+        None => compression::Values::Trace,
+    };
+
+    Ok(compression::Compression { algorithm, values })
+}
+
+// `tlb` é 'entradas[,assoc[,repl]]', os outros argumentos são opcionais.
+pub fn parse_translation(
+    tlb: &str,
+    page_size: Option<&str>,
+    allocation: Option<&str>,
+    walk_cycles: Option<&str>,
+    bsize: usize,
+    adress_bits: usize,
+) -> Result<tlb::Translation, String> {
+    let fields: Vec<&str> = tlb.split(',').map(|field| field.trim()).collect();
+    if fields.len() > 3 {
+        Err(format!(
+            "Malformed argument <tlb>: expected 'entries[,assoc[,repl]]' but got '{}'",
+            tlb
+        ))?
+    }
+
+    let entries = str::parse::<usize>(fields[0])
+        .ok()
+        .filter(|&entries| entries > 0)
+        .ok_or_else(|| conversion_error("tlb", fields[0], "a positive number of entries"))?;
+    let assoc = match fields.get(1).copied() {
+        None | Some("full") => entries,
+        Some(assoc) => str::parse::<usize>(assoc)
+            .ok()
+            .filter(|&assoc| assoc > 0)
+            .ok_or_else(|| conversion_error("tlb", assoc, "a positive associativity"))?,
+    };
+    if entries % assoc != 0 {
+        Err(format!(
+            "Malformed argument <tlb>: {} entries cannot be split in sets of {} ways",
+            entries, assoc
+        ))?
+    }
+    let repl = match fields.get(2) {
+        None => cache::ReplacementPolicy::Lru,
+        Some(repl) => match repl.to_ascii_lowercase().as_ref() {
+            "l" | "lru" | "f" | "fifo" | "r" | "random" => parse_repl(repl),
+            _ => Err(format!(
+                "Malformed argument <tlb>: unknown replacement policy '{}', use lru, fifo or random",
+                repl
+            ))?,
+        },
+    };
+
+    let page_size = match page_size {
+        Some(page_size) => parse_size("page-size", page_size)?
+            .try_power_of_two()
+            .map_err(|page_size| power_of_two_error("page-size", page_size))?,
+        None => 4096,
+    };
+    if page_size < bsize || page_size.trailing_zeros() as usize >= adress_bits {
+        Err(format!(
+            "Malformed argument <page-size>: '{}' must be at least the block size ({}) and fit in {} bit adresses",
+            page_size, bsize, adress_bits
+        ))?
+    }
+
+    // Os valores possíveis para esse argumento estão no cli.yml.
+    let allocation = match allocation.map(|allocation| allocation.to_ascii_lowercase()) {
+        None => tlb::Allocation::Sequential,
+        Some(allocation) => match allocation.as_ref() {
+            "sequential" => tlb::Allocation::Sequential,
+            "random" => tlb::Allocation::Random,
+            "coloured" => tlb::Allocation::Coloured,
+            _ => panic!(),
+        },
+    };
+
+    let walk_cycles = match walk_cycles {
+        Some(walk_cycles) => str::parse::<usize>(walk_cycles)
+            .map_err(|_| conversion_error("walk-cycles", walk_cycles, "usize"))?,
+        None => 30,
+    };
+
+    Ok(tlb::Translation {
+        page_size,
+        allocation,
+        entries,
+        assoc,
+        repl,
+        walk_cycles,
     })
 }
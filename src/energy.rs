@@ -0,0 +1,199 @@
+use crate::cache::{Info, Performance};
+
+// Parâmetros de tecnologia do modelo de energia e área. Os valores padrão são
+// só ilustrativos (próximos de uma SRAM de 45 nm), qualquer um pode ser trocado
+// pelo arquivo de configuração.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Technology {
+    // pJ para ler ou escrever um bit, sem a bitline.
+    pub bit_read: f64,
+    pub bit_write: f64,
+    // pJ a mais por bit lido para cada conjunto que a bitline atravessa.
+    pub bitline_per_row: f64,
+    // pJ por bit de tag comparado.
+    pub tag_compare: f64,
+    // pJ de cada miss fora da cache (o acesso ao próximo nível).
+    pub miss: f64,
+    // nW de leakage por bit do arranjo.
+    pub leakage_per_bit: f64,
+    // Duração da simulação para o leakage: cada hit leva hit_cycles ciclos e
+    // cada miss miss_cycles, de cycle_time ns.
+    pub cycle_time: f64,
+    pub hit_cycles: f64,
+    pub miss_cycles: f64,
+    // µm² por célula e fator para decodificadores, sense amps e fiação.
+    pub cell_area: f64,
+    pub area_overhead: f64,
+}
+
+impl Default for Technology {
+    fn default() -> Technology {
+        Technology {
+            bit_read: 0.004,
+            bit_write: 0.006,
+            bitline_per_row: 0.00002,
+            tag_compare: 0.001,
+            miss: 100.0,
+            leakage_per_bit: 0.05,
+            cycle_time: 0.5,
+            hit_cycles: 1.0,
+            miss_cycles: 20.0,
+            cell_area: 0.35,
+            area_overhead: 1.5,
+        }
+    }
+}
+
+impl Technology {
+    // Uma linha "chave = valor" por parâmetro, com os nomes dos campos. Linhas
+    // vazias e o que vem depois de '#' são ignorados, os parâmetros que não
+    // aparecem ficam com o valor padrão.
+    pub fn parse(text: &str) -> Result<Technology, String> {
+        let mut technology = Technology::default();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |message: &str| format!("Energy config line {}: {}", number + 1, message);
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap().trim();
+            let value = parts
+                .next()
+                .ok_or_else(|| error("expected 'key = value'"))?
+                .trim();
+            let value = str::parse::<f64>(value)
+                .ok()
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .ok_or_else(|| error(&format!("'{}' is not a non-negative number", value)))?;
+
+            let field = match key {
+                "bit_read" => &mut technology.bit_read,
+                "bit_write" => &mut technology.bit_write,
+                "bitline_per_row" => &mut technology.bitline_per_row,
+                "tag_compare" => &mut technology.tag_compare,
+                "miss" => &mut technology.miss,
+                "leakage_per_bit" => &mut technology.leakage_per_bit,
+                "cycle_time" => &mut technology.cycle_time,
+                "hit_cycles" => &mut technology.hit_cycles,
+                "miss_cycles" => &mut technology.miss_cycles,
+                "cell_area" => &mut technology.cell_area,
+                "area_overhead" => &mut technology.area_overhead,
+                _ => Err(error(&format!("unknown parameter '{}'", key)))?,
+            };
+            *field = value;
+        }
+
+        Ok(technology)
+    }
+}
+
+pub fn readfile(filename: &str) -> Result<Technology, String> {
+    let text = std::fs::read_to_string(filename).map_err(|e| format!("{:#?}", e))?;
+    Technology::parse(&text)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Estimate {
+    pub tag_bits: usize,
+    // Bits de cada linha: dados, tag e válido e sujo de cada sub-bloco.
+    pub line_bits: usize,
+    pub total_bits: usize,
+    // pJ para ler uma via (tag e dados) e comparar a tag dela.
+    pub way_read: f64,
+    // pJ para escrever uma linha inteira num miss.
+    pub fill: f64,
+    // Vias lidas: todas em paralelo a cada acesso, ou as do
+    // WayPredictor quando ele foi usado.
+    pub way_probes: usize,
+    // Totais em pJ.
+    pub dynamic: f64,
+    pub leakage: f64,
+    // mW
+    pub leakage_power: f64,
+    // mm²
+    pub area: f64,
+}
+
+impl Estimate {
+    pub fn compute(
+        technology: &Technology,
+        info: &Info,
+        tag_bits: usize,
+        way_probes: Option<usize>,
+        performance: &Performance,
+    ) -> Estimate {
+        let tech = technology;
+        let status_bits = 2 * (info.bsize / info.sub_block);
+        let line_bits = info.bsize * 8 + tag_bits + status_bits;
+        let total_bits = line_bits * info.nsets * info.assoc;
+
+        let way_read = line_bits as f64
+            * (tech.bit_read + info.nsets as f64 * tech.bitline_per_row)
+            + tag_bits as f64 * tech.tag_compare;
+        let fill = line_bits as f64 * tech.bit_write;
+        let way_probes = way_probes.unwrap_or(performance.accesses * info.assoc);
+        let dynamic = way_probes as f64 * way_read + performance.misses as f64 * (fill + tech.miss);
+
+        // nW * ns = 1e-6 pJ
+        let time = (performance.hits as f64 * tech.hit_cycles
+            + performance.misses as f64 * tech.miss_cycles)
+            * tech.cycle_time;
+        let leakage_power = total_bits as f64 * tech.leakage_per_bit;
+
+        Estimate {
+            tag_bits,
+            line_bits,
+            total_bits,
+            way_read,
+            fill,
+            way_probes,
+            dynamic,
+            leakage: leakage_power * time * 1e-6,
+            leakage_power: leakage_power * 1e-6,
+            area: total_bits as f64 * tech.cell_area * tech.area_overhead * 1e-6,
+        }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.dynamic + self.leakage
+    }
+
+    // 0 sem acessos.
+    pub fn per_access(&self, accesses: usize) -> f64 {
+        if accesses == 0 {
+            0.0
+        } else {
+            self.total() / accesses as f64
+        }
+    }
+
+    pub fn format(&self, accesses: usize) -> String {
+        format!(
+            concat!(
+                "energy: {} tag bits, {} bits per line, {} bits\n",
+                "way read energy     = {:.4} pJ\n",
+                "way probes          = {}\n",
+                "dynamic energy      = {:.4} pJ\n",
+                "leakage power       = {:.4} mW\n",
+                "leakage energy      = {:.4} pJ\n",
+                "total energy        = {:.4} pJ\n",
+                "energy per access   = {:.4} pJ\n",
+                "area                = {:.4} mm2"
+            ),
+            self.tag_bits,
+            self.line_bits,
+            self.total_bits,
+            self.way_read,
+            self.way_probes,
+            self.dynamic,
+            self.leakage_power,
+            self.leakage,
+            self.total(),
+            self.per_access(accesses),
+            self.area
+        )
+    }
+}
@@ -0,0 +1,139 @@
+// Como o número do bloco vira o índice do conjunto e a tag.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum IndexFunction {
+    // Os bits logo acima do offset, só funciona com nsets potência de 2.
+    #[default]
+    BitSlice,
+    // bloco % nsets, para qualquer quantidade de conjuntos.
+    Modulo,
+    // bloco % p, onde p é o maior primo <= nsets. Os conjuntos de p em diante
+    // ficam sem uso.
+    PrimeModulo,
+    // XOR de todos os pedaços de log2(nsets) bits do número do bloco, o
+    // primeiro pedaço é o índice do BitSlice.
+    XorFold,
+    // Uma máscara sobre os bits do endereço para cada bit do índice, o bit i
+    // do índice é a paridade de (endereço & linhas[i]).
+    Matrix(Vec<u64>),
+    // Os bits do endereço que formam o índice, do menos para o mais
+    // significativo.
+    Select(Vec<u32>),
+}
+
+impl IndexFunction {
+    // O padrão quando --index não é usado.
+    pub fn default_for(nsets: usize) -> IndexFunction {
+        if nsets.is_power_of_two() {
+            IndexFunction::BitSlice
+        } else {
+            IndexFunction::Modulo
+        }
+    }
+
+    // Quantos dos nsets conjuntos a função alcança, só a PrimeModulo deixa
+    // conjuntos sem uso.
+    pub fn used_sets(&self, nsets: usize) -> usize {
+        match self {
+            IndexFunction::PrimeModulo => largest_prime(nsets),
+            _ => nsets,
+        }
+    }
+}
+
+impl std::fmt::Display for IndexFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            IndexFunction::BitSlice => write!(f, "bits"),
+            IndexFunction::Modulo => write!(f, "modulo"),
+            IndexFunction::PrimeModulo => write!(f, "prime"),
+            IndexFunction::XorFold => write!(f, "xor"),
+            IndexFunction::Matrix(rows) => {
+                let rows: Vec<String> = rows.iter().map(|row| format!("{:#x}", row)).collect();
+                write!(f, "matrix [{}]", rows.join(", "))
+            }
+            IndexFunction::Select(bits) => {
+                let bits: Vec<String> = bits.iter().map(|bit| bit.to_string()).collect();
+                write!(f, "select [{}]", bits.join(", "))
+            }
+        }
+    }
+}
+
+fn is_prime(number: usize) -> bool {
+    number >= 2
+        && (2..)
+            .take_while(|divisor| divisor * divisor <= number)
+            .all(|divisor| number % divisor != 0)
+}
+
+// O maior primo <= number, ou 1 quando não tem nenhum.
+pub fn largest_prime(number: usize) -> usize {
+    (2..=number)
+        .rev()
+        .find(|&candidate| is_prime(candidate))
+        .unwrap_or(1)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Indexer {
+    pub function: IndexFunction,
+    // Quantos conjuntos a função realmente usa.
+    pub modulus: usize,
+    nbits_index: u32,
+    nbits_offset: u32,
+}
+
+impl Indexer {
+    pub fn new(function: &IndexFunction, nsets: usize, bsize: usize) -> Indexer {
+        Indexer {
+            function: function.clone(),
+            modulus: function.used_sets(nsets),
+            nbits_index: nsets.trailing_zeros(),
+            nbits_offset: bsize.trailing_zeros(),
+        }
+    }
+
+    // Índice do conjunto e tag do bloco. O par sempre identifica o bloco:
+    // nos módulos tag * modulus + índice volta para o bloco, no XorFold a tag
+    // são os bits acima do índice (que desfazem o XOR) e nas funções com bits
+    // escolhidos pelo usuário a tag é o bloco inteiro.
+    pub fn split(&self, block: u64) -> (usize, usize) {
+        match &self.function {
+            IndexFunction::BitSlice | IndexFunction::Modulo | IndexFunction::PrimeModulo => {
+                let modulus = self.modulus as u64;
+                ((block % modulus) as usize, (block / modulus) as usize)
+            }
+            IndexFunction::XorFold => {
+                if self.nbits_index == 0 {
+                    return (0, block as usize);
+                }
+
+                let mask = (self.modulus - 1) as u64;
+                let mut index = 0;
+                let mut rest = block;
+                while rest != 0 {
+                    index ^= rest & mask;
+                    rest >>= self.nbits_index;
+                }
+
+                (index as usize, (block >> self.nbits_index) as usize)
+            }
+            IndexFunction::Matrix(rows) => {
+                let index = rows.iter().enumerate().fold(0, |index, (bit, row)| {
+                    let parity = (block & (row >> self.nbits_offset)).count_ones() as usize & 1;
+                    index | (parity << bit)
+                });
+
+                (index, block as usize)
+            }
+            IndexFunction::Select(bits) => {
+                let index = bits.iter().enumerate().fold(0, |index, (position, bit)| {
+                    let value = (block >> (bit - self.nbits_offset)) as usize & 1;
+                    index | (value << position)
+                });
+
+                (index, block as usize)
+            }
+        }
+    }
+}
@@ -1,6 +1,151 @@
 extern crate clap;
 extern crate rand;
 
+// Imprime o erro e sai, usado para todos os erros de argumento.
+fn or_exit<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    })
+}
+
+// Aplica as opções da simulação, que o simulador e o regular_random declaram
+// iguais no cli.yml.
+fn apply_run_options(matches: &clap::ArgMatches, params: &mut csimlib::RunParams) {
+    if let Some(warmup) = matches.value_of("warmup") {
+        params.options.warmup = Some(or_exit(csimlib::parse_warmup(warmup)));
+    }
+    params.options.sampling = or_exit(csimlib::parse_sampling(
+        matches.value_of("sample_sets"),
+        matches
+            .values_of("sample_time")
+            .map(|values| values.collect::<Vec<_>>())
+            .as_deref(),
+    ));
+    if let Some(seed) = matches.value_of("repl_seed") {
+        params.options.seed = Some(or_exit(csimlib::parse_repl_seed(seed)));
+    }
+    if let Some(organisation) = matches.value_of("organisation") {
+        params.options.organisation =
+            or_exit(csimlib::parse_organisation(organisation, params.nsets));
+    }
+    if let Some(compression) = matches.value_of("compression") {
+        params.options.compression = Some(or_exit(csimlib::parse_compression(
+            compression,
+            matches.value_of("value_model"),
+        )));
+    }
+    if let Some(energy) = matches.value_of("energy") {
+        params.options.technology = Some(or_exit(csimlib::energy::readfile(energy)));
+    }
+    if let Some(predictor) = matches.value_of("way_predictor") {
+        params.options.way_predictor = Some(or_exit(csimlib::parse_way_predictor(
+            predictor,
+            matches.value_of("predictor_entries"),
+        )));
+    }
+    if let Some(sub_block) = matches.value_of("sub_block") {
+        params.options.sub_block = Some(or_exit(csimlib::parse_sub_block(sub_block, params.bsize)));
+    }
+    if let Some(tlb) = matches.value_of("tlb") {
+        params.options.translation = Some(or_exit(csimlib::parse_translation(
+            tlb,
+            matches.value_of("page_size"),
+            matches.value_of("page_allocation"),
+            matches.value_of("walk_cycles"),
+            params.bsize,
+            params.adress_bits,
+        )));
+    }
+    params.options.compare_translation = matches.is_present("tlb_compare");
+    if let Some(index) = matches.value_of("index") {
+        params.options.index = or_exit(csimlib::parse_index(
+            index,
+            matches
+                .values_of("index_matrix")
+                .map(|values| values.collect::<Vec<_>>())
+                .as_deref(),
+            matches
+                .values_of("index_bits")
+                .map(|values| values.collect::<Vec<_>>())
+                .as_deref(),
+            params.nsets,
+            params.bsize,
+        ));
+    }
+}
+
+// Roda a simulação com as opções e escreve os relatórios pedidos.
+fn simulate(matches: &clap::ArgMatches, mut params: csimlib::RunParams) {
+    let output = csimlib::parse_output(matches.value_of("output").unwrap());
+    apply_run_options(matches, &mut params);
+    let phases = if let Some(window) = matches.value_of("window") {
+        let (window, detector) = or_exit(csimlib::parse_window_options(
+            window,
+            matches.value_of("phase_threshold").unwrap(),
+            matches.value_of("ws_threshold").unwrap(),
+        ));
+        params.options.window = Some(window);
+        Some(detector)
+    } else {
+        None
+    };
+    let destination = csimlib::report::Destination {
+        path: matches.value_of("output_file").map(|path| path.to_owned()),
+        append: matches.is_present("append"),
+    };
+
+    // Com --repeat só as estatísticas entre as seeds são mostradas.
+    if let Some(repeat) = matches.value_of("repeat") {
+        let repeat = or_exit(csimlib::parse_repeat(repeat));
+        let repetitions = csimlib::repeat::Repetitions::run(&params, repeat);
+        or_exit(repetitions.write(output, &destination));
+        return;
+    }
+
+    let set_report = if matches.is_present("set_report") {
+        Some(or_exit(csimlib::parse_set_report(
+            matches.value_of("hottest").unwrap(),
+        )))
+    } else {
+        None
+    };
+
+    let cache = csimlib::run_with(&params);
+    let sampled = csimlib::sampling::Summary::compute(&cache, &params);
+    or_exit(csimlib::report::write(
+        &cache,
+        output,
+        params.verbosity,
+        &destination,
+        sampled.as_ref(),
+    ));
+    if let Some(hottest) = set_report {
+        print!("{}", csimlib::report::set_report(&cache, hottest));
+    }
+    if let Some(detector) = phases {
+        detector.print(&cache.windows);
+    }
+    if matches.is_present("index_report") {
+        // A função padrão simulada com a mesma seed para comparar.
+        let mut baseline = params.clone();
+        baseline.options.index = csimlib::indexing::IndexFunction::default_for(params.nsets);
+        baseline.options.seed = Some(cache.info.seed);
+        let baseline = csimlib::run_with(&baseline);
+        print!(
+            "{}",
+            csimlib::report::index_report(
+                &baseline,
+                &cache,
+                &params.options.index,
+                or_exit(csimlib::parse_set_report(
+                    matches.value_of("hottest").unwrap()
+                ))
+            )
+        );
+    }
+}
+
 fn main() {
     let yaml = clap::load_yaml!("cli.yml");
     let app = clap::App::from_yaml(yaml);
@@ -20,34 +165,200 @@ fn main() {
                 .unwrap_or_else(|| rand::thread_rng().next_u64().to_string()),
         );
 
-        let params = csimlib::parse_and_validate(
+        let params = or_exit(csimlib::parse_and_validate(
             submatches.value_of("nsets").unwrap(),
             submatches.value_of("bsize").unwrap(),
             submatches.value_of("assoc").unwrap(),
             submatches.value_of("repl").unwrap(),
             submatches.value_of("verbosity").unwrap(),
             csimlib::Either::Right(input),
-        )
-        .unwrap_or_else(|err| {
-            eprintln!("{}", err);
-            std::process::exit(1);
-        });
+        ));
+        simulate(submatches, params);
+    } else if let Some(submatches) = matches.subcommand_matches("convert") {
+        let input_format = or_exit(csimlib::parse_format(
+            submatches.value_of("format").unwrap(),
+            submatches.value_of("endianness").unwrap(),
+            submatches.value_of("width").unwrap(),
+            submatches.value_of("trailing").unwrap(),
+            submatches.value_of("access_size").unwrap(),
+        ));
+        let output_format = or_exit(csimlib::parse_format(
+            submatches.value_of("output_format").unwrap(),
+            submatches.value_of("output_endianness").unwrap(),
+            submatches.value_of("output_width").unwrap(),
+            "reject",
+            "1",
+        ));
+        let filter = or_exit(csimlib::parse_filter(
+            &submatches
+                .values_of("only")
+                .map(|values| values.collect())
+                .unwrap_or_else(Vec::new),
+            submatches.value_of("min_adress"),
+            submatches.value_of("max_adress"),
+        ));
+
+        let records = or_exit(csimlib::trace::readfile(
+            submatches.value_of("input_file").unwrap(),
+            &input_format,
+        ));
+        let total = records.len();
+        let kept: Vec<csimlib::trace::Record> = records
+            .into_iter()
+            .filter(|record| filter.keeps(record))
+            .collect();
+
+        or_exit(csimlib::trace::writefile(
+            submatches.value_of("output_file").unwrap(),
+            &kept,
+            &output_format,
+        ));
+        println!("{} of {} records written", kept.len(), total);
+    } else if let Some(submatches) = matches.subcommand_matches("trace-stats") {
+        let format = or_exit(csimlib::parse_format(
+            submatches.value_of("format").unwrap(),
+            submatches.value_of("endianness").unwrap(),
+            submatches.value_of("width").unwrap(),
+            submatches.value_of("trailing").unwrap(),
+            submatches.value_of("access_size").unwrap(),
+        ));
+        let (bsizes, window) = or_exit(csimlib::parse_stats_options(
+            &submatches.values_of("bsizes").unwrap().collect::<Vec<_>>(),
+            submatches.value_of("window").unwrap(),
+        ));
+
+        let records = or_exit(csimlib::trace::readfile(
+            submatches.value_of("input_file").unwrap(),
+            &format,
+        ));
+        csimlib::analysis::TraceStats::compute(&records, &bsizes, window).print();
+    } else if let Some(submatches) = matches.subcommand_matches("reuse-distance") {
+        let format = or_exit(csimlib::parse_format(
+            submatches.value_of("format").unwrap(),
+            submatches.value_of("endianness").unwrap(),
+            submatches.value_of("width").unwrap(),
+            submatches.value_of("trailing").unwrap(),
+            submatches.value_of("access_size").unwrap(),
+        ));
+        let bsize = or_exit(csimlib::parse_bsize(submatches.value_of("bsize").unwrap()));
+
+        let records = or_exit(csimlib::trace::readfile(
+            submatches.value_of("input_file").unwrap(),
+            &format,
+        ));
+        csimlib::analysis::ReuseProfile::compute(&records, bsize).print();
+    } else if let Some(submatches) = matches.subcommand_matches("all-assoc") {
+        let format = or_exit(csimlib::parse_format(
+            submatches.value_of("format").unwrap(),
+            submatches.value_of("endianness").unwrap(),
+            submatches.value_of("width").unwrap(),
+            submatches.value_of("trailing").unwrap(),
+            submatches.value_of("access_size").unwrap(),
+        ));
+        let (bsize, nsets, assocs) = or_exit(csimlib::parse_grid_options(
+            submatches.value_of("bsize").unwrap(),
+            &submatches.values_of("nsets").unwrap().collect::<Vec<_>>(),
+            &submatches.values_of("assoc").unwrap().collect::<Vec<_>>(),
+        ));
 
-        csimlib::run_with(&params).print_perf(params.verbosity);
+        let records = or_exit(csimlib::trace::readfile(
+            submatches.value_of("input_file").unwrap(),
+            &format,
+        ));
+        csimlib::stacksim::StackGrid::simulate(&records, bsize, &nsets, &assocs).print();
+    } else if let Some(submatches) = matches.subcommand_matches("sweep") {
+        let format = or_exit(csimlib::parse_format(
+            submatches.value_of("format").unwrap(),
+            submatches.value_of("endianness").unwrap(),
+            submatches.value_of("width").unwrap(),
+            submatches.value_of("trailing").unwrap(),
+            submatches.value_of("access_size").unwrap(),
+        ));
+        let (mut configs, jobs) = or_exit(csimlib::parse_sweep_options(
+            &submatches.values_of("nsets").unwrap().collect::<Vec<_>>(),
+            &submatches.values_of("bsize").unwrap().collect::<Vec<_>>(),
+            &submatches.values_of("assoc").unwrap().collect::<Vec<_>>(),
+            &submatches.values_of("repl").unwrap().collect::<Vec<_>>(),
+            submatches.value_of("jobs"),
+            format.width.bits(),
+        ));
+
+        let search = or_exit(csimlib::parse_search_options(
+            submatches.value_of("budget"),
+            submatches.is_present("area_model"),
+            submatches
+                .values_of("latency_model")
+                .map(|values| values.collect::<Vec<_>>())
+                .as_deref(),
+            format.width.bits(),
+        ));
+        // Não adianta simular o que não cabe no orçamento.
+        if let Some(search) = &search {
+            configs.retain(|config| search.fits(config));
+        }
+
+        // O trace é lido uma vez só e compartilhado entre as threads.
+        let records = std::sync::Arc::new(or_exit(csimlib::trace::readfile(
+            submatches.value_of("input_file").unwrap(),
+            &format,
+        )));
+        let mut options = csimlib::RunOptions::default();
+        if let Some(warmup) = submatches.value_of("warmup") {
+            options.warmup = Some(or_exit(csimlib::parse_warmup(warmup)));
+        }
+        options.sampling = or_exit(csimlib::parse_sampling(
+            submatches.value_of("sample_sets"),
+            submatches
+                .values_of("sample_time")
+                .map(|values| values.collect::<Vec<_>>())
+                .as_deref(),
+        ));
+        if let Some(seed) = submatches.value_of("repl_seed") {
+            options.seed = Some(or_exit(csimlib::parse_repl_seed(seed)));
+        }
+        if let Some(organisation) = submatches.value_of("organisation") {
+            // Os nsets do sweep já são potências de 2.
+            options.organisation = or_exit(csimlib::parse_organisation(organisation, 1));
+        }
+        if let Some(energy) = submatches.value_of("energy") {
+            options.technology = Some(or_exit(csimlib::energy::readfile(energy)));
+        }
+
+        let results = csimlib::sweep::run(&configs, &records, format.width.bits(), jobs, &options);
+        let destination = csimlib::report::Destination {
+            path: submatches
+                .value_of("output_file")
+                .map(|path| path.to_owned()),
+            append: submatches.is_present("append"),
+        };
+        let output = csimlib::parse_output(submatches.value_of("output").unwrap());
+        or_exit(csimlib::sweep::write_results(
+            &results,
+            output,
+            &destination,
+            search.as_ref(),
+        ));
+        // No JSON e no CSV a fronteira já está nas linhas de cada configuração.
+        if let (Some(search), csimlib::report::Output::Text) = (search, output) {
+            search.print(&results);
+        }
     } else {
-        let params = csimlib::parse_and_validate(
+        let format = or_exit(csimlib::parse_format(
+            matches.value_of("format").unwrap(),
+            matches.value_of("endianness").unwrap(),
+            matches.value_of("width").unwrap(),
+            matches.value_of("trailing").unwrap(),
+            matches.value_of("access_size").unwrap(),
+        ));
+
+        let params = or_exit(csimlib::parse_and_validate(
             matches.value_of("nsets").unwrap(),
             matches.value_of("bsize").unwrap(),
             matches.value_of("assoc").unwrap(),
             matches.value_of("repl").unwrap(),
             matches.value_of("verbosity").unwrap(),
-            csimlib::Either::Left(matches.value_of("input_file").unwrap()),
-        )
-        .unwrap_or_else(|err| {
-            eprintln!("{}", err);
-            std::process::exit(1);
-        });
-
-        csimlib::run_with(&params).print_perf(params.verbosity);
+            csimlib::Either::Left((matches.value_of("input_file").unwrap(), format)),
+        ));
+        simulate(&matches, params);
     };
 }
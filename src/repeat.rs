@@ -0,0 +1,155 @@
+use crate::cache::Performance;
+use crate::report::{self, Output};
+use crate::stats::{self, Estimate};
+
+// A mesma simulação repetida com seeds diferentes para a política Random.
+#[derive(Debug)]
+pub struct Repetitions {
+    pub seeds: Vec<u64>,
+    pub performances: Vec<Performance>,
+}
+
+impl Repetitions {
+    // As seeds são base, base + 1, ..., onde base é RunOptions::seed ou uma
+    // seed aleatória.
+    pub fn run(params: &crate::RunParams, count: usize) -> Repetitions {
+        use rand::RngCore;
+
+        let base = params
+            .options
+            .seed
+            .unwrap_or_else(|| rand::thread_rng().next_u64());
+        let seeds: Vec<u64> = (0..count as u64)
+            .map(|offset| base.wrapping_add(offset))
+            .collect();
+
+        let performances = seeds
+            .iter()
+            .map(|&seed| {
+                let mut params = params.clone();
+                params.options.seed = Some(seed);
+                crate::run_with(&params).performance
+            })
+            .collect();
+
+        Repetitions {
+            seeds,
+            performances,
+        }
+    }
+
+    // Nome e valor de cada repetição para todos os contadores e taxas.
+    pub fn metrics(&self) -> Vec<(&'static str, Vec<f64>)> {
+        let metric = |name, value: &dyn Fn(&Performance) -> f64| {
+            (name, self.performances.iter().map(value).collect())
+        };
+        let rate = |numerator, denominator| report::rate(numerator, denominator).unwrap_or(0.0);
+
+        vec![
+            metric("accesses", &|perf| perf.accesses as f64),
+            metric("hits", &|perf| perf.hits as f64),
+            metric("misses", &|perf| perf.misses as f64),
+            metric("compulsory_misses", &|perf| perf.compulsory_misses as f64),
+            metric("capacity_misses", &|perf| perf.capacity_misses as f64),
+            metric("conflict_misses", &|perf| perf.conflict_misses as f64),
+            metric("hit_rate", &|perf| rate(perf.hits, perf.accesses)),
+            metric("miss_rate", &|perf| rate(perf.misses, perf.accesses)),
+            metric("compulsory_fraction", &|perf| {
+                rate(perf.compulsory_misses, perf.misses)
+            }),
+            metric("capacity_fraction", &|perf| {
+                rate(perf.capacity_misses, perf.misses)
+            }),
+            metric("conflict_fraction", &|perf| {
+                rate(perf.conflict_misses, perf.misses)
+            }),
+        ]
+    }
+
+    // Média, desvio padrão e intervalo de confiança de 95% de cada métrica.
+    pub fn summary(&self) -> Vec<(&'static str, f64, Estimate)> {
+        self.metrics()
+            .into_iter()
+            .map(|(name, values)| {
+                (
+                    name,
+                    stats::standard_deviation(&values),
+                    stats::mean_estimate(&values),
+                )
+            })
+            .collect()
+    }
+
+    pub fn write(&self, output: Output, destination: &report::Destination) -> Result<(), String> {
+        let summary = self.summary();
+        let seeds: Vec<String> = self.seeds.iter().map(|seed| seed.to_string()).collect();
+
+        match output {
+            Output::Text => {
+                let mut text = format!("{} seeds: {}\n", self.seeds.len(), seeds.join(", "));
+                text += &format!(
+                    "{:<20} {:>16} {:>16} {:>16} {:>16}",
+                    "metric", "mean", "std dev", "95% ci low", "95% ci high"
+                );
+                for (name, deviation, estimate) in &summary {
+                    text += &format!(
+                        "\n{:<20} {:>16.6} {:>16.6} {:>16.6} {:>16.6}",
+                        name,
+                        estimate.value,
+                        deviation,
+                        estimate.low(),
+                        estimate.high()
+                    );
+                }
+                report::emit(destination, None, &text)
+            }
+            Output::Json => {
+                let metrics: Vec<String> = summary
+                    .iter()
+                    .map(|(name, deviation, estimate)| {
+                        format!(
+                            "\"{}\": {{\"mean\": {}, \"std_dev\": {}, \"ci95\": {}}}",
+                            name,
+                            estimate.value,
+                            deviation,
+                            report::json_estimate(estimate)
+                        )
+                    })
+                    .collect();
+
+                report::emit(
+                    destination,
+                    None,
+                    &format!(
+                        "{{\"schema_version\": {}, \"seeds\": [{}], \"metrics\": {{{}}}}}",
+                        report::JSON_SCHEMA_VERSION,
+                        seeds.join(", "),
+                        metrics.join(", ")
+                    ),
+                )
+            }
+            Output::Csv => {
+                let rows: Vec<String> = summary
+                    .iter()
+                    .map(|(name, deviation, estimate)| {
+                        format!(
+                            "{},{},{},{},{},{}",
+                            name,
+                            self.seeds.len(),
+                            estimate.value,
+                            deviation,
+                            estimate.low(),
+                            estimate.high()
+                        )
+                    })
+                    .collect();
+
+                report::emit(
+                    destination,
+                    Some("metric,seeds,mean,std_dev,ci95_low,ci95_high"),
+                    &rows.join("\n"),
+                )
+            }
+        }
+    }
+}
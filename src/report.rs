@@ -0,0 +1,767 @@
+use crate::cache::{Cache, Kind, Performance, ReplacementPolicy};
+use crate::compression::{Stats as CompressionStats, Values};
+use crate::energy::Estimate as EnergyEstimate;
+use crate::indexing::IndexFunction;
+use crate::sampling::{Sampling, Summary};
+use crate::stats::Estimate;
+use crate::sweep::Config;
+use crate::tlb::{Stats as TranslationStats, Translation};
+use crate::waypred::{Predictor, WayPredictor};
+
+// Muda sempre que um campo do JSON muda de nome, tipo ou significado, campos
+// novos podem ser adicionados sem mudar a versão.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+// Tons do mapa de calor dos conjuntos, do menos ao mais acessado.
+const HEAT_SHADES: &[u8] = b" .:-=+*#%@";
+const HEAT_MAP_COLUMNS: usize = 64;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Output {
+    // O formato original do print_perf, que depende da verbosidade.
+    Text,
+    Json,
+    // Uma linha por simulação, com cabeçalho quando o destino começa vazio.
+    Csv,
+}
+
+// Para onde a saída vai, None é a saída padrão.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Destination {
+    pub path: Option<String>,
+    // Adiciona ao final do arquivo ao invez de sobrescrever.
+    pub append: bool,
+}
+
+// None quando não tem como calcular (nenhum acesso ou nenhum miss).
+pub fn rate(numerator: usize, denominator: usize) -> Option<f64> {
+    if denominator == 0 {
+        None
+    } else {
+        Some(numerator as f64 / denominator as f64)
+    }
+}
+
+fn json_rate(numerator: usize, denominator: usize) -> String {
+    rate(numerator, denominator)
+        .map(|rate| rate.to_string())
+        .unwrap_or_else(|| "null".to_owned())
+}
+
+// Entre aspas, ou null sem o valor.
+fn json_number<T: std::fmt::Display>(value: Option<T>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "null".to_owned())
+}
+
+fn json_string<T: std::fmt::Display>(value: Option<T>) -> String {
+    value
+        .map(|value| format!("\"{}\"", value))
+        .unwrap_or_else(|| "null".to_owned())
+}
+
+// Um objeto JSON numa linha só, com a configuração, os contadores, as taxas
+// derivadas deles e a seed da política Random.
+pub fn json(config: &Config, kind: Kind, performance: &Performance, seed: u64) -> String {
+    let perf = performance;
+
+    format!(
+        concat!(
+            "{{\"schema_version\": {}, ",
+            "\"config\": {{\"nsets\": {}, \"bsize\": {}, \"assoc\": {}, \"repl\": \"{}\", ",
+            "\"kind\": \"{}\", \"size\": {}, \"total_slots\": {}, \"index\": \"{}\", ",
+            "\"organisation\": \"{}\", \"sub_block\": {}, \"compression\": {}, ",
+            "\"value_model\": {}, \"translation\": {}}}, ",
+            "\"seed\": {}, ",
+            "\"counters\": {}, ",
+            "\"rates\": {{\"hit_rate\": {}, \"miss_rate\": {}, \"compulsory_fraction\": {}, ",
+            "\"capacity_fraction\": {}, \"conflict_fraction\": {}}}}}"
+        ),
+        JSON_SCHEMA_VERSION,
+        config.nsets,
+        config.bsize,
+        config.assoc,
+        config.repl,
+        kind,
+        config.size(),
+        config.total_slots(),
+        config.index,
+        config.organisation,
+        config.sub_block,
+        json_string(config.compression.map(|compression| compression.algorithm)),
+        json_string(config.compression.map(|compression| compression.values)),
+        config
+            .translation
+            .as_ref()
+            .map(json_translation_config)
+            .unwrap_or_else(|| "null".to_owned()),
+        seed,
+        json_counters(perf),
+        json_rate(perf.hits, perf.accesses),
+        json_rate(perf.misses, perf.accesses),
+        json_rate(perf.compulsory_misses, perf.misses),
+        json_rate(perf.capacity_misses, perf.misses),
+        json_rate(perf.conflict_misses, perf.misses),
+    )
+}
+
+fn json_counters(performance: &Performance) -> String {
+    let perf = performance;
+
+    format!(
+        concat!(
+            "{{\"accesses\": {}, \"hits\": {}, \"misses\": {}, ",
+            "\"compulsory_misses\": {}, \"capacity_misses\": {}, \"conflict_misses\": {}, ",
+            "\"split_accesses\": {}, \"slots_occupied\": {}}}"
+        ),
+        perf.accesses,
+        perf.hits,
+        perf.misses,
+        perf.compulsory_misses,
+        perf.capacity_misses,
+        perf.conflict_misses,
+        perf.split_accesses,
+        perf.slots_occupied,
+    )
+}
+
+// Contadores das caches com setores, None quando os blocos não foram
+// divididos em sub-blocos.
+fn sectors(cache: &Cache) -> Option<(usize, usize, usize, usize, usize)> {
+    let info = &cache.info;
+    if info.sub_block == info.bsize {
+        return None;
+    }
+
+    let perf = &cache.performance;
+    let sector_misses = perf.misses - perf.sub_block_misses;
+    Some((
+        sector_misses,
+        perf.sub_block_misses,
+        perf.misses * info.sub_block,
+        perf.unfetched_bytes,
+        perf.written_back_bytes,
+    ))
+}
+
+pub fn sector_report(cache: &Cache) -> Option<String> {
+    let (sector_misses, sub_block_misses, fetched, unfetched, written_back) = sectors(cache)?;
+
+    Some(format!(
+        concat!(
+            "sectors: {} sub-blocks of {} bytes\n",
+            "sector misses       = {}\n",
+            "sub-block misses    = {}\n",
+            "fetched bytes       = {}\n",
+            "unfetched bytes     = {}\n",
+            "written back bytes  = {}"
+        ),
+        cache.info.bsize / cache.info.sub_block,
+        cache.info.sub_block,
+        sector_misses,
+        sub_block_misses,
+        fetched,
+        unfetched,
+        written_back
+    ))
+}
+
+fn json_sectors(cache: &Cache) -> Option<String> {
+    let (sector_misses, sub_block_misses, fetched, unfetched, written_back) = sectors(cache)?;
+
+    Some(format!(
+        concat!(
+            "{{\"sub_block\": {}, \"sector_misses\": {}, \"sub_block_misses\": {}, ",
+            "\"fetched_bytes\": {}, \"unfetched_bytes\": {}, \"written_back_bytes\": {}}}"
+        ),
+        cache.info.sub_block, sector_misses, sub_block_misses, fetched, unfetched, written_back
+    ))
+}
+
+fn json_way_predictor(predictor: &WayPredictor, assoc: usize) -> String {
+    let entries = match predictor.predictor {
+        Predictor::Mru => "null".to_owned(),
+        Predictor::Pc { entries } => entries.to_string(),
+    };
+    let name = match predictor.predictor {
+        Predictor::Mru => "mru",
+        Predictor::Pc { .. } => "pc",
+    };
+
+    format!(
+        concat!(
+            "{{\"predictor\": \"{}\", \"entries\": {}, \"correct\": {}, ",
+            "\"mispredicted\": {}, \"misses\": {}, \"accuracy\": {}, ",
+            "\"probes\": {}, \"parallel_probes\": {}}}"
+        ),
+        name,
+        entries,
+        predictor.correct,
+        predictor.mispredicted,
+        predictor.misses,
+        json_rate(
+            predictor.correct,
+            predictor.correct + predictor.mispredicted
+        ),
+        predictor.probes(assoc),
+        predictor.parallel_probes(assoc)
+    )
+}
+
+pub fn json_energy(energy: &EnergyEstimate, accesses: usize) -> String {
+    format!(
+        concat!(
+            "{{\"tag_bits\": {}, \"line_bits\": {}, \"total_bits\": {}, ",
+            "\"way_read_pj\": {}, \"way_probes\": {}, \"dynamic_pj\": {}, ",
+            "\"leakage_mw\": {}, \"leakage_pj\": {}, \"total_pj\": {}, ",
+            "\"per_access_pj\": {}, \"area_mm2\": {}}}"
+        ),
+        energy.tag_bits,
+        energy.line_bits,
+        energy.total_bits,
+        energy.way_read,
+        energy.way_probes,
+        energy.dynamic,
+        energy.leakage_power,
+        energy.leakage,
+        energy.total(),
+        energy.per_access(accesses),
+        energy.area
+    )
+}
+
+fn json_compression(stats: &CompressionStats, hits: usize) -> String {
+    let values = match stats.compression.values {
+        Values::Trace => "\"trace\"".to_owned(),
+        Values::Synthetic { zero, narrow } => {
+            format!("{{\"zero\": {}, \"narrow\": {}}}", zero, narrow)
+        }
+    };
+
+    format!(
+        concat!(
+            "{{\"algorithm\": \"{}\", \"values\": {}, \"fills\": {}, \"ratio\": {}, ",
+            "\"resident_lines\": {}, \"baseline_hits\": {}, \"extra_hits\": {}}}"
+        ),
+        stats.compression.algorithm,
+        values,
+        stats.fills,
+        stats.ratio(),
+        stats.resident_lines,
+        stats.baseline_hits,
+        stats.extra_hits(hits)
+    )
+}
+
+// Só as opções da --tlb, os contadores ficam no json_translation.
+fn json_translation_config(translation: &Translation) -> String {
+    format!(
+        concat!(
+            "{{\"page_size\": {}, \"allocation\": \"{}\", \"tlb_entries\": {}, ",
+            "\"tlb_assoc\": {}, \"tlb_repl\": \"{}\", \"cycles_per_walk\": {}}}"
+        ),
+        translation.page_size,
+        translation.allocation,
+        translation.entries,
+        translation.assoc,
+        translation.repl,
+        translation.walk_cycles
+    )
+}
+
+fn json_translation(stats: &TranslationStats, conflicts: usize) -> String {
+    let translation = &stats.translation;
+    let comparison = stats.comparison;
+    format!(
+        concat!(
+            "{{\"page_size\": {}, \"allocation\": \"{}\", \"colours\": {}, ",
+            "\"tlb_entries\": {}, \"tlb_assoc\": {}, \"tlb_repl\": \"{}\", ",
+            "\"tlb_accesses\": {}, \"tlb_hits\": {}, \"tlb_hit_rate\": {}, ",
+            "\"page_walks\": {}, \"walk_cycles\": {}, \"pages\": {}, ",
+            "\"conflict_misses\": {}, \"virtual_conflict_misses\": {}, ",
+            "\"compared_allocation\": \"{}\", \"compared_conflict_misses\": {}}}"
+        ),
+        translation.page_size,
+        translation.allocation,
+        stats.colours,
+        translation.entries,
+        translation.assoc,
+        translation.repl,
+        stats.tlb.accesses,
+        stats.tlb.hits,
+        json_rate(stats.tlb.hits, stats.tlb.accesses),
+        stats.tlb.misses,
+        stats.walk_cycles(),
+        stats.pages,
+        conflicts,
+        json_number(comparison.map(|comparison| comparison.virtual_conflicts)),
+        json_string(comparison.map(|comparison| comparison.allocation)),
+        json_number(comparison.map(|comparison| comparison.conflicts))
+    )
+}
+
+// Limites do intervalo de confiança ficam null quando ele é infinito.
+pub fn json_estimate(estimate: &Estimate) -> String {
+    let bound = |bound: f64| {
+        if bound.is_finite() {
+            bound.to_string()
+        } else {
+            "null".to_owned()
+        }
+    };
+
+    format!(
+        "{{\"value\": {}, \"low\": {}, \"high\": {}}}",
+        estimate.value,
+        bound(estimate.low()),
+        bound(estimate.high())
+    )
+}
+
+pub fn json_sampling(summary: &Summary) -> String {
+    let method = match summary.sampling {
+        Sampling::Sets { every } => format!("\"method\": \"sets\", \"every\": {}", every),
+        Sampling::Time {
+            period,
+            warmup,
+            length,
+        } => format!(
+            "\"method\": \"time\", \"period\": {}, \"warmup\": {}, \"length\": {}",
+            period, warmup, length
+        ),
+    };
+
+    format!(
+        concat!(
+            "{{{}, \"units\": {}, \"population\": {}, ",
+            "\"accesses\": {}, \"misses\": {}, \"miss_rate\": {}}}"
+        ),
+        method,
+        summary.units,
+        summary.population,
+        json_estimate(&summary.accesses),
+        json_estimate(&summary.misses),
+        json_estimate(&summary.miss_rate)
+    )
+}
+
+// Adiciona um campo ao final de um objeto JSON gerado por json().
+pub fn with_field(mut json: String, name: &str, value: &str) -> String {
+    json.pop();
+    json + &format!(", \"{}\": {}}}", name, value)
+}
+
+pub fn csv_header() -> &'static str {
+    concat!(
+        "nsets,bsize,assoc,repl,size,accesses,hits,misses,compulsory,capacity,conflict,",
+        "hit_rate,miss_rate,compulsory_fraction,capacity_fraction,conflict_fraction,seed,",
+        "index,organisation,sub_block,compression,value_model,",
+        "page_size,allocation,tlb_entries,tlb_assoc,tlb_repl,cycles_per_walk,",
+        "energy_pj,energy_per_access_pj,area_mm2"
+    )
+}
+
+// Entre aspas quando o valor tem vírgula (as funções matrix e select).
+fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn csv_optional<T: std::fmt::Display>(value: Option<T>) -> String {
+    value
+        .map(|value| csv_field(&value.to_string()))
+        .unwrap_or_default()
+}
+
+// As colunas de energia ficam vazias sem arquivo de tecnologia, para as linhas
+// de simulações com e sem ele poderem ir para o mesmo arquivo (--append).
+pub fn csv_row(
+    config: &Config,
+    performance: &Performance,
+    seed: u64,
+    energy: Option<&EnergyEstimate>,
+) -> String {
+    let perf = performance;
+    // Sem acessos (ou sem misses) as taxas ficam 0, como no print_perf.
+    let rate = |numerator, denominator| rate(numerator, denominator).unwrap_or(0.0);
+
+    format!(
+        "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        config.nsets,
+        config.bsize,
+        config.assoc,
+        config.repl,
+        config.size(),
+        perf.accesses,
+        perf.hits,
+        perf.misses,
+        perf.compulsory_misses,
+        perf.capacity_misses,
+        perf.conflict_misses,
+        rate(perf.hits, perf.accesses),
+        rate(perf.misses, perf.accesses),
+        rate(perf.compulsory_misses, perf.misses),
+        rate(perf.capacity_misses, perf.misses),
+        rate(perf.conflict_misses, perf.misses),
+        seed,
+        csv_field(&config.index.to_string()),
+        config.organisation,
+        config.sub_block,
+        // Vazias sem compressão.
+        csv_optional(config.compression.map(|compression| compression.algorithm)),
+        csv_optional(config.compression.map(|compression| compression.values)),
+        // Também vazias sem a TLB.
+        match &config.translation {
+            Some(translation) => format!(
+                "{},{},{},{},{},{}",
+                translation.page_size,
+                translation.allocation,
+                translation.entries,
+                translation.assoc,
+                translation.repl,
+                translation.walk_cycles
+            ),
+            None => ",,,,,".to_owned(),
+        },
+        match energy {
+            Some(energy) => format!(
+                "{},{},{}",
+                energy.total(),
+                energy.per_access(perf.accesses),
+                energy.area
+            ),
+            None => ",,".to_owned(),
+        }
+    )
+}
+
+// Escreve body no destino, header só é escrito quando o destino começa vazio
+// (um arquivo novo, sobrescrito ou vazio, ou a saída padrão).
+pub fn emit(destination: &Destination, header: Option<&str>, body: &str) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut text = String::new();
+    let starts_empty = match &destination.path {
+        Some(path) if destination.append => std::fs::metadata(path)
+            .map(|metadata| metadata.len() == 0)
+            .unwrap_or(true),
+        _ => true,
+    };
+    if let (Some(header), true) = (header, starts_empty) {
+        text += header;
+        text += "\n";
+    }
+    text += body;
+    text += "\n";
+
+    match &destination.path {
+        Some(path) => std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(destination.append)
+            .truncate(!destination.append)
+            .open(path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .map_err(|e| format!("{:#?}", e)),
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+pub fn write(
+    cache: &Cache,
+    output: Output,
+    verbosity: u8,
+    destination: &Destination,
+    sampled: Option<&Summary>,
+) -> Result<(), String> {
+    let config = Config::from(&cache.info);
+    // Só tem aquecimento quando RunOptions::warmup foi usado, nesse caso
+    // performance tem só os acessos depois dele.
+    let warmup = Some(&cache.warmup_performance).filter(|warmup| warmup.accesses > 0);
+
+    match output {
+        Output::Text => {
+            let mut text = String::new();
+            if let Some(warmup) = warmup {
+                text += "warm-up (excluded)\n";
+                text += &warmup.format(verbosity);
+                text += "\nsteady state\n";
+            }
+            text += &cache.format_perf(verbosity);
+            // Sem ela não dá para repetir uma simulação com a política Random. A
+            // verbosidade 1 é só a linha da especificação do trabalho.
+            if cache.info.repl == ReplacementPolicy::Random && verbosity != 1 {
+                text += &format!("\nseed = {}", cache.info.seed);
+            }
+            if let Some(sectors) = sector_report(cache) {
+                text += "\n";
+                text += &sectors;
+            }
+            if let Some(predictor) = &cache.way_predictor {
+                text += "\n";
+                text += &predictor.format(cache.info.assoc);
+            }
+            if let Some(energy) = &cache.energy {
+                text += "\n";
+                text += &energy.format(cache.performance.accesses);
+            }
+            if let Some(compression) = &cache.compression {
+                text += "\n";
+                text += &compression.format(cache.performance.hits, cache.info.total_slots);
+            }
+            if let Some(translation) = &cache.translation {
+                text += "\n";
+                text += &translation.format(cache.performance.conflict_misses);
+            }
+            if let Some(summary) = sampled {
+                text += "\n";
+                text += &summary.format();
+            }
+            emit(destination, None, &text)
+        }
+        Output::Json => {
+            let mut text = json(&config, cache.kind, &cache.performance, cache.info.seed);
+            if let Some(warmup) = warmup {
+                text = with_field(text, "warmup", &json_counters(warmup));
+            }
+            if let Some(summary) = sampled {
+                text = with_field(text, "sampling", &json_sampling(summary));
+            }
+            if let Some(sectors) = json_sectors(cache) {
+                text = with_field(text, "sectors", &sectors);
+            }
+            if let Some(predictor) = &cache.way_predictor {
+                text = with_field(
+                    text,
+                    "way_prediction",
+                    &json_way_predictor(predictor, cache.info.assoc),
+                );
+            }
+            if let Some(energy) = &cache.energy {
+                text = with_field(
+                    text,
+                    "energy",
+                    &json_energy(energy, cache.performance.accesses),
+                );
+            }
+            if let Some(compression) = &cache.compression {
+                text = with_field(
+                    text,
+                    "compression",
+                    &json_compression(compression, cache.performance.hits),
+                );
+            }
+            if let Some(translation) = &cache.translation {
+                text = with_field(
+                    text,
+                    "translation",
+                    &json_translation(translation, cache.performance.conflict_misses),
+                );
+            }
+            emit(destination, None, &text)
+        }
+        // O CSV tem só os números do regime permanente, sem extrapolação.
+        Output::Csv => {
+            let row = csv_row(
+                &config,
+                &cache.performance,
+                cache.info.seed,
+                cache.energy.as_ref(),
+            );
+            emit(destination, Some(csv_header()), &row)
+        }
+    }
+}
+
+// Desvio padrão (da população) dividido pela média, 0 quando a média é 0.
+pub fn coefficient_of_variation(values: &[usize]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let mean = values.iter().sum::<usize>() as f64 / values.len() as f64;
+    if mean == 0.0 {
+        return 0.0;
+    }
+    let variance = values
+        .iter()
+        .map(|&value| (value as f64 - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+
+    variance.sqrt() / mean
+}
+
+pub fn set_report(cache: &Cache, hottest: usize) -> String {
+    let sets = &cache.set_performance;
+    let accesses: Vec<usize> = sets.iter().map(|set| set.accesses()).collect();
+    let misses: Vec<usize> = sets.iter().map(|set| set.misses).collect();
+    let evictions: Vec<usize> = sets.iter().map(|set| set.evictions).collect();
+    let mean = |values: &[usize]| values.iter().sum::<usize>() as f64 / values.len().max(1) as f64;
+
+    let mut report = format!("set imbalance ({} sets)\n", sets.len());
+    for (name, values) in &[
+        ("accesses", &accesses),
+        ("misses", &misses),
+        ("evictions", &evictions),
+    ] {
+        report += &format!(
+            "{:<10} mean = {:.3}, cv = {:.3}\n",
+            name,
+            mean(values),
+            coefficient_of_variation(values)
+        );
+    }
+    report += &format!(
+        "unused sets = {}\n",
+        accesses.iter().filter(|&&count| count == 0).count()
+    );
+
+    // Mais acessados primeiro, empates pelo menor índice.
+    let mut order: Vec<usize> = (0..sets.len()).collect();
+    order.sort_by(|&a, &b| accesses[b].cmp(&accesses[a]).then(a.cmp(&b)));
+
+    report += &format!(
+        "\nhottest sets\n{:>10} {:>10} {:>10} {:>10} {:>10} {:>10}\n",
+        "set", "accesses", "hits", "misses", "evictions", "miss rate"
+    );
+    for &index in order.iter().take(hottest) {
+        report += &format!(
+            "{:>10} {:>10} {:>10} {:>10} {:>10} {:>10.4}\n",
+            index,
+            accesses[index],
+            sets[index].hits,
+            sets[index].misses,
+            sets[index].evictions,
+            rate(sets[index].misses, accesses[index]).unwrap_or(0.0)
+        );
+    }
+
+    let max = accesses.iter().cloned().max().unwrap_or(0);
+    report += &format!(
+        "\nheat map (accesses per set, '{}' = none to '{}' = {}, {} sets per row)\n",
+        HEAT_SHADES[0] as char,
+        HEAT_SHADES[HEAT_SHADES.len() - 1] as char,
+        max,
+        HEAT_MAP_COLUMNS
+    );
+    for (row, chunk) in accesses.chunks(HEAT_MAP_COLUMNS).enumerate() {
+        let shades: String = chunk
+            .iter()
+            .map(|&count| {
+                if count == 0 {
+                    HEAT_SHADES[0] as char
+                } else {
+                    // Qualquer acesso já ganha pelo menos o segundo tom.
+                    HEAT_SHADES[1 + count * (HEAT_SHADES.len() - 2) / max] as char
+                }
+            })
+            .collect();
+        report += &format!("{:>10} |{}|\n", row * HEAT_MAP_COLUMNS, shades);
+    }
+
+    report
+}
+
+// Compara os conflitos por conjunto de uma função de índice com os da função
+// padrão, simulada com o mesmo trace. `rows` conjuntos com mais conflitos na
+// função padrão são listados.
+pub fn index_report(
+    baseline: &Cache,
+    hashed: &Cache,
+    function: &IndexFunction,
+    rows: usize,
+) -> String {
+    let conflicts = |cache: &Cache| -> Vec<usize> {
+        cache
+            .set_performance
+            .iter()
+            .map(|set| set.conflicts)
+            .collect()
+    };
+    let (before, after) = (conflicts(baseline), conflicts(hashed));
+    // Fração dos conflitos da função padrão que sumiram, negativa quando aumentam.
+    let reduction = |before: usize, after: usize| {
+        if before == 0 {
+            if after == 0 {
+                0.0
+            } else {
+                f64::NEG_INFINITY
+            }
+        } else {
+            (before as f64 - after as f64) / before as f64
+        }
+    };
+
+    let mut report = format!(
+        "index function {} vs {}\n{:<20} {:>10} {:>10} {:>10}\n",
+        function,
+        IndexFunction::default_for(baseline.info.nsets),
+        "",
+        "default",
+        "hashed",
+        "reduction"
+    );
+    let mut line = |name: &str, before: usize, after: usize| {
+        report += &format!(
+            "{:<20} {:>10} {:>10} {:>10.4}\n",
+            name,
+            before,
+            after,
+            reduction(before, after)
+        );
+    };
+    line(
+        "misses",
+        baseline.performance.misses,
+        hashed.performance.misses,
+    );
+    line(
+        "conflict misses",
+        baseline.performance.conflict_misses,
+        hashed.performance.conflict_misses,
+    );
+    line(
+        "max set conflicts",
+        before.iter().cloned().max().unwrap_or(0),
+        after.iter().cloned().max().unwrap_or(0),
+    );
+    line(
+        "sets with conflicts",
+        before.iter().filter(|&&count| count > 0).count(),
+        after.iter().filter(|&&count| count > 0).count(),
+    );
+    report += &format!(
+        "{:<20} {:>10.3} {:>10.3}\n",
+        "conflict cv",
+        coefficient_of_variation(&before),
+        coefficient_of_variation(&after)
+    );
+
+    // Mais conflitos na função padrão primeiro, empates pelo menor índice.
+    let mut order: Vec<usize> = (0..before.len()).collect();
+    order.sort_by(|&a, &b| before[b].cmp(&before[a]).then(a.cmp(&b)));
+
+    report += &format!(
+        "\nsets with most conflicts\n{:>10} {:>10} {:>10} {:>10}\n",
+        "set", "default", "hashed", "reduction"
+    );
+    for &index in order.iter().take(rows) {
+        report += &format!(
+            "{:>10} {:>10} {:>10} {:>10.4}\n",
+            index,
+            before[index],
+            after[index],
+            reduction(before[index], after[index])
+        );
+    }
+
+    report
+}
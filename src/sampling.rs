@@ -0,0 +1,165 @@
+use crate::cache::Cache;
+use crate::stats::{self, Estimate};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Sampling {
+    // Simula só os conjuntos com índice múltiplo de `every`, os acessos aos
+    // outros são ignorados. A divisão dos misses em capacidade e conflito
+    // não vale nesse modo porque a cache nunca enche.
+    Sets {
+        every: usize,
+    },
+    // A cada `period` acessos do trace, `warmup` acessos só aquecem a cache,
+    // os `length` seguintes são medidos e o resto é pulado sem simular.
+    Time {
+        period: usize,
+        warmup: usize,
+        length: usize,
+    },
+}
+
+// O que o run_with faz com um acesso do trace.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Phase {
+    Simulate,
+    Warmup,
+    // first e last marcam o começo e o fim de uma janela medida.
+    Measure { first: bool, last: bool },
+    Skip,
+}
+
+impl Sampling {
+    pub fn keeps_set(&self, index: usize) -> bool {
+        match *self {
+            Sampling::Sets { every } => index % every == 0,
+            Sampling::Time { .. } => true,
+        }
+    }
+
+    // `position` conta a partir do fim do aquecimento global (RunOptions::warmup).
+    pub fn phase(&self, position: usize) -> Phase {
+        match *self {
+            Sampling::Sets { .. } => Phase::Simulate,
+            Sampling::Time {
+                period,
+                warmup,
+                length,
+            } => {
+                let position = position % period;
+                if position < warmup {
+                    Phase::Warmup
+                } else if position < warmup + length {
+                    Phase::Measure {
+                        first: position == warmup,
+                        last: position + 1 == warmup + length,
+                    }
+                } else {
+                    Phase::Skip
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Sampling {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Sampling::Sets { every } => write!(f, "1 of every {} sets", every),
+            Sampling::Time {
+                period,
+                warmup,
+                length,
+            } => write!(
+                f,
+                "{} warm-up and {} measured accesses every {}",
+                warmup, length, period
+            ),
+        }
+    }
+}
+
+// Métricas da cache inteira (ou do trace inteiro) extrapoladas a partir das
+// unidades amostradas, que são os conjuntos ou as janelas medidas.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Summary {
+    pub sampling: Sampling,
+    pub units: usize,
+    pub population: f64,
+    pub accesses: Estimate,
+    pub misses: Estimate,
+    pub miss_rate: Estimate,
+}
+
+impl Summary {
+    // None quando params não usa amostragem.
+    pub fn compute(cache: &Cache, params: &crate::RunParams) -> Option<Summary> {
+        let sampling = params.options.sampling?;
+        let (units, population): (Vec<(usize, usize)>, f64) = match sampling {
+            Sampling::Sets { every } => (
+                cache
+                    .set_performance
+                    .iter()
+                    .step_by(every)
+                    .map(|set| (set.accesses(), set.misses))
+                    .collect(),
+                cache.info.nsets as f64,
+            ),
+            Sampling::Time { length, .. } => {
+                // Acessos do trace depois do aquecimento global.
+                let measured = params.input.len()
+                    - params
+                        .options
+                        .warmup
+                        .map(|warmup| warmup.accesses(params.input.len()))
+                        .unwrap_or(0);
+
+                (
+                    cache
+                        .samples
+                        .iter()
+                        .map(|sample| (sample.accesses, sample.misses))
+                        .collect(),
+                    (measured as f64 / length as f64).max(cache.samples.len() as f64),
+                )
+            }
+        };
+
+        let accesses: Vec<f64> = units.iter().map(|&(accesses, _)| accesses as f64).collect();
+        let misses: Vec<f64> = units.iter().map(|&(_, misses)| misses as f64).collect();
+
+        Some(Summary {
+            sampling,
+            units: units.len(),
+            population,
+            accesses: stats::total_estimate(&accesses, population),
+            misses: stats::total_estimate(&misses, population),
+            miss_rate: stats::ratio_estimate(&misses, &accesses, population),
+        })
+    }
+
+    pub fn hit_rate(&self) -> Estimate {
+        Estimate {
+            value: 1.0 - self.miss_rate.value,
+            half_width: self.miss_rate.half_width,
+        }
+    }
+
+    pub fn format(&self) -> String {
+        format!(
+            concat!(
+                "sampling: {} ({} of {:.0} units)\n",
+                "estimated accesses  = {}\n",
+                "estimated misses    = {}\n",
+                "estimated miss rate = {}\n",
+                "estimated hit rate  = {}"
+            ),
+            self.sampling,
+            self.units,
+            self.population,
+            self.accesses,
+            self.misses,
+            self.miss_rate,
+            self.hit_rate()
+        )
+    }
+}
@@ -0,0 +1,172 @@
+use crate::sweep::{Config, SweepResult};
+
+// Modelo linear simples de latência de acesso, em ciclos:
+// base + per_size_doubling * log2(size) + per_assoc_doubling * log2(assoc).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LatencyModel {
+    pub base: f64,
+    pub per_size_doubling: f64,
+    pub per_assoc_doubling: f64,
+}
+
+impl LatencyModel {
+    pub fn latency(&self, config: &Config) -> f64 {
+        self.base
+            + self.per_size_doubling * (config.size() as f64).log2()
+            + self.per_assoc_doubling * (config.assoc as f64).log2()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Search {
+    // Tamanho total máximo (Info::size), em bytes.
+    pub budget: usize,
+    // Inclui a área (bits de dados + tag + válido) como mais um objetivo.
+    pub area: bool,
+    pub latency: Option<LatencyModel>,
+    pub adress_bits: usize,
+}
+
+// Bits de armazenamento da cache: dados, tag e bit de válido de cada linha.
+pub fn area_bits(config: &Config, adress_bits: usize) -> usize {
+    let nbits_offset = config.bsize.trailing_zeros() as usize;
+    let nbits_index = config.nsets.trailing_zeros() as usize;
+    let nbits_tag = adress_bits.saturating_sub(nbits_index + nbits_offset);
+
+    config.nsets * config.assoc * (config.bsize * 8 + nbits_tag + 1)
+}
+
+pub fn miss_rate(result: &SweepResult) -> f64 {
+    if result.performance.accesses == 0 {
+        0.0
+    } else {
+        result.performance.misses as f64 / result.performance.accesses as f64
+    }
+}
+
+impl Search {
+    pub fn fits(&self, config: &Config) -> bool {
+        config.size() <= self.budget
+    }
+
+    // Todos os objetivos são minimizados.
+    fn objectives(&self, result: &SweepResult) -> Vec<f64> {
+        let mut objectives = vec![
+            miss_rate(result),
+            result.config.size() as f64,
+            result.config.assoc as f64,
+        ];
+        if self.area {
+            objectives.push(area_bits(&result.config, self.adress_bits) as f64);
+        }
+        if let Some(model) = self.latency {
+            objectives.push(model.latency(&result.config));
+        }
+
+        objectives
+    }
+
+    // Índices (em results) das configurações dentro do orçamento que não são
+    // dominadas por nenhuma outra, ou seja, que nenhuma outra é pelo menos tão
+    // boa em todos os objetivos e melhor em algum.
+    pub fn pareto_frontier(&self, results: &[SweepResult]) -> Vec<usize> {
+        let candidates: Vec<(usize, Vec<f64>)> = results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| self.fits(&result.config))
+            .map(|(index, result)| (index, self.objectives(result)))
+            .collect();
+
+        let dominates = |a: &[f64], b: &[f64]| {
+            a.iter().zip(b).all(|(a, b)| a <= b) && a.iter().zip(b).any(|(a, b)| a < b)
+        };
+
+        candidates
+            .iter()
+            .filter(|(_, objectives)| {
+                !candidates
+                    .iter()
+                    .any(|(_, other)| dominates(other, objectives))
+            })
+            .map(|(index, _)| *index)
+            .collect()
+    }
+
+    // A configuração dentro do orçamento com a menor taxa de miss, empates
+    // vão para a menor, depois para a menos associativa e depois para a de
+    // menor latência.
+    pub fn best(&self, results: &[SweepResult]) -> Option<usize> {
+        results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| self.fits(&result.config))
+            .min_by(|(_, a), (_, b)| {
+                let latency = |result: &SweepResult| {
+                    self.latency
+                        .map(|model| model.latency(&result.config))
+                        .unwrap_or(0.0)
+                };
+
+                miss_rate(a)
+                    .partial_cmp(&miss_rate(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(a.config.size().cmp(&b.config.size()))
+                    .then(a.config.assoc.cmp(&b.config.assoc))
+                    .then(
+                        latency(a)
+                            .partial_cmp(&latency(b))
+                            .unwrap_or(std::cmp::Ordering::Equal),
+                    )
+            })
+            .map(|(index, _)| index)
+    }
+
+    fn print_row(&self, result: &SweepResult) {
+        print!(
+            "{}, {}, {}, {}, {}, {}",
+            result.config.nsets,
+            result.config.bsize,
+            result.config.assoc,
+            result.config.repl,
+            result.config.size(),
+            miss_rate(result)
+        );
+        if self.area {
+            print!(", {}", area_bits(&result.config, self.adress_bits));
+        }
+        if let Some(model) = self.latency {
+            print!(", {}", model.latency(&result.config));
+        }
+        println!();
+    }
+
+    pub fn print(&self, results: &[SweepResult]) {
+        let mut header = "nsets, bsize, assoc, repl, size, miss rate".to_owned();
+        if self.area {
+            header += ", area bits";
+        }
+        if self.latency.is_some() {
+            header += ", latency";
+        }
+
+        let mut frontier = self.pareto_frontier(results);
+        frontier.sort_by_key(|&index| (results[index].config.size(), results[index].config.assoc));
+
+        println!();
+        println!("pareto frontier (budget = {} bytes)", self.budget);
+        println!("{}", header);
+        for index in frontier {
+            self.print_row(&results[index]);
+        }
+
+        println!();
+        match self.best(results) {
+            Some(index) => {
+                println!("best under budget");
+                println!("{}", header);
+                self.print_row(&results[index]);
+            }
+            None => println!("no configuration fits in {} bytes", self.budget),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+// Funções de índice da cache skewed-associative (Seznec), uma por via.
+//
+// Com A1, A2, A3... os pedaços de log2(nsets) bits do número do bloco, do
+// mais baixo para o mais alto, a via w < log2(nsets) usa A1 ^ rotl(A2, w).
+// Dois blocos que brigam pelo mesmo conjunto numa via quase nunca brigam nas
+// outras. Só existem log2(nsets) rotações diferentes, então a partir daí a
+// via w usa ainda um hash do bloco sem A1 misturado com w / log2(nsets). Não
+// dá para usar só os pedaços mais altos: com endereços curtos eles são 0 e a
+// via repetiria a função de outra.
+
+fn rotate_left(value: u64, amount: u32, bits: u32) -> u64 {
+    let mask = (1u64 << bits) - 1;
+    let amount = amount % bits;
+    if amount == 0 {
+        value & mask
+    } else {
+        ((value << amount) | (value >> (bits - amount))) & mask
+    }
+}
+
+// Finalizador do splitmix64: cada bit da entrada muda metade dos da saída.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// O conjunto do bloco em cada uma das `assoc` vias, nsets é potência de 2.
+pub fn indexes(block: u64, nsets: usize, assoc: usize) -> Vec<usize> {
+    let bits = nsets.trailing_zeros();
+    if bits == 0 {
+        return vec![0; assoc];
+    }
+
+    let mask = (nsets - 1) as u64;
+    let chunk = |number: u32| {
+        block
+            .checked_shr(number * bits)
+            .map(|shifted| shifted & mask)
+            .unwrap_or(0)
+    };
+
+    (0..assoc as u32)
+        .map(|way| {
+            let round = way / bits;
+            let extra = if round == 0 {
+                0
+            } else {
+                mix((block >> bits) ^ mix(u64::from(round))) & mask
+            };
+            (chunk(0) ^ rotate_left(chunk(1), way, bits) ^ extra) as usize
+        })
+        .collect()
+}
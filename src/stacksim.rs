@@ -0,0 +1,106 @@
+use crate::trace::Record;
+
+// Simulação de pilha (Mattson et al.): numa cache LRU o conteúdo de um
+// conjunto com k vias é sempre o topo da pilha LRU daquele conjunto, então a
+// posição do bloco na pilha diz para quais associatividades o acesso é hit.
+// Uma pilha por conjunto para cada nsets da grade dá os misses de todas as
+// combinações nsets x assoc numa passada só.
+#[derive(Debug)]
+pub struct StackGrid {
+    pub bsize: usize,
+    pub nsets: Vec<usize>,
+    pub assocs: Vec<usize>,
+    pub references: usize,
+    // misses[i][j] = misses da cache com nsets[i] conjuntos e assocs[j] vias.
+    pub misses: Vec<Vec<usize>>,
+}
+
+impl StackGrid {
+    pub fn simulate(
+        input: &[Record],
+        bsize: usize,
+        nsets: &[usize],
+        assocs: &[usize],
+    ) -> StackGrid {
+        let nbits_offset = bsize.trailing_zeros() as usize;
+        // As pilhas não precisam ser mais fundas que a maior associatividade,
+        // qualquer coisa além disso é miss para toda a grade.
+        let depth = assocs.iter().cloned().max().unwrap_or(0);
+
+        let mut stacks: Vec<Vec<Vec<u64>>> = nsets
+            .iter()
+            .map(|&sets| vec![Vec::with_capacity(depth); sets])
+            .collect();
+        // hits_at[i][d] = acessos encontrados na posição d da pilha do conjunto.
+        let mut hits_at: Vec<Vec<usize>> = vec![vec![0; depth]; nsets.len()];
+        let mut references = 0;
+
+        for record in input {
+            for block in record.blocks(nbits_offset) {
+                references += 1;
+
+                for (grid_index, &sets) in nsets.iter().enumerate() {
+                    let stack = &mut stacks[grid_index][(block as usize) & (sets - 1)];
+
+                    match stack.iter().position(|&tag| tag == block) {
+                        Some(position) => {
+                            hits_at[grid_index][position] += 1;
+                            stack[..=position].rotate_right(1);
+                        }
+                        None if depth > 0 => {
+                            if stack.len() < depth {
+                                stack.push(block);
+                            } else {
+                                *stack.last_mut().unwrap() = block;
+                            }
+                            stack.rotate_right(1);
+                        }
+                        None => (),
+                    }
+                }
+            }
+        }
+
+        let misses = hits_at
+            .iter()
+            .map(|hits| {
+                assocs
+                    .iter()
+                    .map(|&assoc| references - hits.iter().take(assoc).sum::<usize>())
+                    .collect()
+            })
+            .collect();
+
+        StackGrid {
+            bsize,
+            nsets: nsets.to_vec(),
+            assocs: assocs.to_vec(),
+            references,
+            misses,
+        }
+    }
+
+    pub fn print(&self) {
+        println!(
+            "{:>10} {:>10} {:>10} {:>14} {:>10} {:>12}",
+            "nsets", "bsize", "assoc", "size", "misses", "miss ratio"
+        );
+        for (sets, row) in self.nsets.iter().zip(&self.misses) {
+            for (assoc, misses) in self.assocs.iter().zip(row) {
+                println!(
+                    "{:>10} {:>10} {:>10} {:>14} {:>10} {:>12.6}",
+                    sets,
+                    self.bsize,
+                    assoc,
+                    sets * self.bsize * assoc,
+                    misses,
+                    if self.references == 0 {
+                        0.0
+                    } else {
+                        *misses as f64 / self.references as f64
+                    }
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// Um valor estimado e a metade do intervalo de confiança de 95% em volta dele.
+// half_width é infinito quando não tem amostras suficientes para estimar.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Estimate {
+    pub value: f64,
+    pub half_width: f64,
+}
+
+impl Estimate {
+    pub fn low(&self) -> f64 {
+        self.value - self.half_width
+    }
+
+    pub fn high(&self) -> f64 {
+        self.value + self.half_width
+    }
+}
+
+impl std::fmt::Display for Estimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.6} ± {:.6} (95% CI [{:.6}, {:.6}])",
+            self.value,
+            self.half_width,
+            self.low(),
+            self.high()
+        )
+    }
+}
+
+// Quantil 0.975 da distribuição t de Student, acima de 30 graus de liberdade
+// a normal já é uma boa aproximação.
+pub fn t_95(degrees_of_freedom: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+
+    match degrees_of_freedom {
+        0 => f64::INFINITY,
+        df if df <= TABLE.len() => TABLE[df - 1],
+        _ => 1.960,
+    }
+}
+
+pub fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len().max(1) as f64
+}
+
+// Desvio padrão da amostra (dividido por n - 1), 0 com menos de 2 valores.
+pub fn standard_deviation(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+
+    let mean = mean(values);
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / (values.len() - 1) as f64;
+
+    variance.sqrt()
+}
+
+// Correção de população finita, some quando a amostra é a população inteira.
+fn finite_population_correction(samples: usize, population: f64) -> f64 {
+    (1.0 - samples as f64 / population).max(0.0)
+}
+
+fn half_width(samples: usize, variance_of_estimate: f64) -> f64 {
+    if variance_of_estimate == 0.0 {
+        0.0
+    } else {
+        t_95(samples.saturating_sub(1)) * variance_of_estimate.sqrt()
+    }
+}
+
+// Média de repetições independentes (população infinita).
+pub fn mean_estimate(values: &[f64]) -> Estimate {
+    if values.len() < 2 {
+        return Estimate {
+            value: mean(values),
+            half_width: f64::INFINITY,
+        };
+    }
+
+    let deviation = standard_deviation(values);
+    Estimate {
+        value: mean(values),
+        half_width: half_width(values.len(), deviation.powi(2) / values.len() as f64),
+    }
+}
+
+// Total de uma população de `population` unidades a partir de uma amostra
+// aleatória simples delas.
+pub fn total_estimate(values: &[f64], population: f64) -> Estimate {
+    let fpc = finite_population_correction(values.len(), population);
+    if values.len() < 2 && fpc > 0.0 {
+        return Estimate {
+            value: population * mean(values),
+            half_width: f64::INFINITY,
+        };
+    }
+
+    let deviation = standard_deviation(values);
+    Estimate {
+        value: population * mean(values),
+        half_width: half_width(
+            values.len(),
+            population.powi(2) * fpc * deviation.powi(2) / values.len() as f64,
+        ),
+    }
+}
+
+// Razão sum(numerators) / sum(denominators) com a variância do estimador de
+// razão (linearizado), como a taxa de miss estimada a partir de conjuntos ou
+// janelas amostrados.
+pub fn ratio_estimate(numerators: &[f64], denominators: &[f64], population: f64) -> Estimate {
+    let samples = numerators.len();
+    let total_denominator: f64 = denominators.iter().sum();
+    let ratio = if total_denominator == 0.0 {
+        0.0
+    } else {
+        numerators.iter().sum::<f64>() / total_denominator
+    };
+
+    let fpc = finite_population_correction(samples, population);
+    if samples < 2 || total_denominator == 0.0 {
+        return Estimate {
+            value: ratio,
+            half_width: if fpc > 0.0 { f64::INFINITY } else { 0.0 },
+        };
+    }
+
+    let mean_denominator = total_denominator / samples as f64;
+    let residuals: f64 = numerators
+        .iter()
+        .zip(denominators)
+        .map(|(numerator, denominator)| (numerator - ratio * denominator).powi(2))
+        .sum::<f64>()
+        / (samples - 1) as f64;
+
+    Estimate {
+        value: ratio,
+        half_width: half_width(
+            samples,
+            fpc * residuals / (samples as f64 * mean_denominator.powi(2)),
+        ),
+    }
+}
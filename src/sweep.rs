@@ -0,0 +1,308 @@
+use crate::cache::{Info, Kind, Organisation, Performance, ReplacementPolicy};
+use crate::compression::Compression;
+use crate::indexing::IndexFunction;
+use crate::report::{self, Output};
+use crate::sampling::Summary;
+use crate::search::Search;
+use crate::tlb::Translation;
+use crate::trace::Record;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Config {
+    pub nsets: usize,
+    pub bsize: usize,
+    pub assoc: usize,
+    pub repl: ReplacementPolicy,
+    pub index: IndexFunction,
+    pub organisation: Organisation,
+    // Igual a bsize sem setores.
+    pub sub_block: usize,
+    pub compression: Option<Compression>,
+    pub translation: Option<Translation>,
+}
+
+impl Config {
+    // As outras opções ficam com o padrão, como numa Cache::create.
+    pub fn new(nsets: usize, bsize: usize, assoc: usize, repl: ReplacementPolicy) -> Config {
+        Config {
+            nsets,
+            bsize,
+            assoc,
+            repl,
+            index: IndexFunction::default(),
+            organisation: Organisation::default(),
+            sub_block: bsize,
+            compression: None,
+            translation: None,
+        }
+    }
+
+    // A skewed indexa cada via com a própria função de bits.
+    pub fn used_sets(&self) -> usize {
+        match self.organisation {
+            Organisation::SetAssociative => self.index.used_sets(self.nsets),
+            Organisation::Skewed => self.nsets,
+        }
+    }
+
+    pub fn total_slots(&self) -> usize {
+        self.used_sets() * self.assoc
+    }
+
+    pub fn size(&self) -> usize {
+        self.total_slots() * self.bsize
+    }
+}
+
+impl From<&Info> for Config {
+    fn from(info: &Info) -> Config {
+        Config {
+            nsets: info.nsets,
+            bsize: info.bsize,
+            assoc: info.assoc,
+            repl: info.repl,
+            index: info.index.clone(),
+            organisation: info.organisation,
+            sub_block: info.sub_block,
+            compression: info.compression,
+            translation: info.translation,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SweepResult {
+    pub config: Config,
+    pub performance: Performance,
+    pub seed: u64,
+    // Só quando RunOptions::sampling é usado.
+    pub sampled: Option<Summary>,
+    // Só quando RunOptions::technology é usado.
+    pub energy: Option<crate::energy::Estimate>,
+}
+
+// Todas as combinações, na ordem nsets > bsize > assoc > repl.
+pub fn configurations(
+    nsets: &[usize],
+    bsizes: &[usize],
+    assocs: &[usize],
+    repls: &[ReplacementPolicy],
+) -> Vec<Config> {
+    let mut configs = Vec::new();
+    for &nsets in nsets {
+        for &bsize in bsizes {
+            for &assoc in assocs {
+                for &repl in repls {
+                    configs.push(Config::new(nsets, bsize, assoc, repl));
+                }
+            }
+        }
+    }
+
+    configs
+}
+
+// Roda cada configuração com a sua própria Cache em `jobs` threads, todas
+// lendo o mesmo trace e com as mesmas opções. Os resultados voltam na mesma
+// ordem de `configs`.
+pub fn run(
+    configs: &[Config],
+    input: &Arc<Vec<Record>>,
+    adress_bits: usize,
+    jobs: usize,
+    options: &crate::RunOptions,
+) -> Vec<SweepResult> {
+    let configs = Arc::new(configs.to_vec());
+    let next = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+
+    let workers: Vec<std::thread::JoinHandle<()>> = (0..jobs.max(1).min(configs.len()))
+        .map(|_| {
+            let configs = Arc::clone(&configs);
+            let next = Arc::clone(&next);
+            let input = Arc::clone(input);
+            let sender = sender.clone();
+            let options = options.clone();
+
+            std::thread::spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let config = match configs.get(index) {
+                    Some(config) => config,
+                    None => break,
+                };
+
+                let params = crate::RunParams {
+                    nsets: config.nsets,
+                    bsize: config.bsize,
+                    assoc: config.assoc,
+                    repl: config.repl,
+                    verbosity: 1,
+                    adress_bits,
+                    input: Arc::clone(&input),
+                    options: options.clone(),
+                };
+                let cache = crate::run_with(&params);
+                let sampled = Summary::compute(&cache, &params);
+
+                sender
+                    .send((
+                        index,
+                        SweepResult {
+                            // Com as opções da simulação, que não variam no sweep.
+                            config: Config::from(&cache.info),
+                            performance: cache.performance,
+                            seed: cache.info.seed,
+                            sampled,
+                            energy: cache.energy,
+                        },
+                    ))
+                    .expect("A thread principal parou de receber resultados");
+            })
+        })
+        .collect();
+    drop(sender);
+
+    let mut results: Vec<(usize, SweepResult)> = receiver.iter().collect();
+    for worker in workers {
+        worker.join().expect("Uma thread do sweep entrou em pânico");
+    }
+    results.sort_by_key(|(index, _)| *index);
+
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+// Com `search` o JSON e o CSV dizem em cada configuração se ela está na
+// fronteira de Pareto e se é a melhor no orçamento, o texto mostra as tabelas
+// do Search::print depois.
+pub fn write_results(
+    results: &[SweepResult],
+    output: Output,
+    destination: &report::Destination,
+    search: Option<&Search>,
+) -> Result<(), String> {
+    let frontier = search
+        .map(|search| search.pareto_frontier(results))
+        .unwrap_or_default();
+    let best = search.and_then(|search| search.best(results));
+
+    let rows: Vec<String> = results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| match output {
+            Output::Text => {
+                let perf = &result.performance;
+                let rate =
+                    |numerator, denominator| report::rate(numerator, denominator).unwrap_or(0.0);
+
+                let row = format!(
+                    "{}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}",
+                    result.config.nsets,
+                    result.config.bsize,
+                    result.config.assoc,
+                    result.config.repl,
+                    result.config.size(),
+                    perf.accesses,
+                    rate(perf.hits, perf.accesses),
+                    rate(perf.misses, perf.accesses),
+                    rate(perf.compulsory_misses, perf.misses),
+                    rate(perf.capacity_misses, perf.misses),
+                    rate(perf.conflict_misses, perf.misses)
+                );
+                let row = match &result.sampled {
+                    Some(summary) => format!(
+                        "{}, {}, {}",
+                        row,
+                        summary.miss_rate.low(),
+                        summary.miss_rate.high()
+                    ),
+                    None => row,
+                };
+                match &result.energy {
+                    Some(energy) => format!(
+                        "{}, {}, {}, {}",
+                        row,
+                        energy.total(),
+                        energy.per_access(perf.accesses),
+                        energy.area
+                    ),
+                    None => row,
+                }
+            }
+            // Um objeto por linha (JSON lines), no mesmo formato de uma simulação só.
+            Output::Json => {
+                let json =
+                    report::json(&result.config, Kind::Both, &result.performance, result.seed);
+                let json = match &result.sampled {
+                    Some(summary) => {
+                        report::with_field(json, "sampling", &report::json_sampling(summary))
+                    }
+                    None => json,
+                };
+                let json = match &result.energy {
+                    Some(energy) => report::with_field(
+                        json,
+                        "energy",
+                        &report::json_energy(energy, result.performance.accesses),
+                    ),
+                    None => json,
+                };
+                match search {
+                    Some(search) => {
+                        let json = report::with_field(json, "budget", &search.budget.to_string());
+                        let json = report::with_field(
+                            json,
+                            "pareto_optimal",
+                            &frontier.contains(&index).to_string(),
+                        );
+                        report::with_field(
+                            json,
+                            "best_under_budget",
+                            &(best == Some(index)).to_string(),
+                        )
+                    }
+                    None => json,
+                }
+            }
+            Output::Csv => {
+                let row = report::csv_row(
+                    &result.config,
+                    &result.performance,
+                    result.seed,
+                    result.energy.as_ref(),
+                );
+                match search {
+                    Some(_) => format!(
+                        "{},{},{}",
+                        row,
+                        frontier.contains(&index),
+                        best == Some(index)
+                    ),
+                    None => row,
+                }
+            }
+        })
+        .collect();
+
+    let mut text_header = String::from(
+        "nsets, bsize, assoc, repl, size, accesses, hit rate, miss rate, compulsory, capacity, conflict",
+    );
+    if results.iter().any(|result| result.sampled.is_some()) {
+        text_header += ", miss rate low, miss rate high";
+    }
+    let mut csv_header = report::csv_header().to_owned();
+    if results.iter().any(|result| result.energy.is_some()) {
+        text_header += ", energy (pJ), energy per access (pJ), area (mm2)";
+    }
+    if search.is_some() {
+        csv_header += ",pareto_optimal,best_under_budget";
+    }
+    let header = match output {
+        Output::Text => Some(text_header),
+        Output::Json => None,
+        Output::Csv => Some(csv_header),
+    };
+
+    report::emit(destination, header.as_deref(), &rows.join("\n"))
+}
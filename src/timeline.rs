@@ -0,0 +1,83 @@
+use crate::cache::Performance;
+
+#[derive(Debug, Clone)]
+pub struct Window {
+    // Índice do primeiro acesso do trace na janela.
+    pub start: usize,
+    // Acessos do trace na janela (o último pode ser menor).
+    pub length: usize,
+    // Quanto cada contador andou durante a janela.
+    pub delta: Performance,
+    // Blocos distintos tocados na janela.
+    pub blocks: usize,
+}
+
+impl Window {
+    pub fn miss_rate(&self) -> f64 {
+        crate::report::rate(self.delta.misses, self.delta.accesses).unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PhaseDetector {
+    // Diferença absoluta da taxa de miss para a janela anterior.
+    pub miss_rate_threshold: f64,
+    // Diferença relativa do working set para a janela anterior.
+    pub working_set_threshold: f64,
+}
+
+impl PhaseDetector {
+    // Índices das janelas que começam uma fase nova (a primeira sempre começa).
+    pub fn detect(&self, windows: &[Window]) -> Vec<usize> {
+        let mut phases = Vec::new();
+        if !windows.is_empty() {
+            phases.push(0);
+        }
+
+        for (index, pair) in windows.windows(2).enumerate() {
+            let (previous, current) = (&pair[0], &pair[1]);
+
+            let miss_rate_change = (current.miss_rate() - previous.miss_rate()).abs();
+            let working_set_change = (current.blocks as f64 - previous.blocks as f64).abs()
+                / (previous.blocks.max(1) as f64);
+
+            if miss_rate_change > self.miss_rate_threshold
+                || working_set_change > self.working_set_threshold
+            {
+                phases.push(index + 1);
+            }
+        }
+
+        phases
+    }
+
+    pub fn print(&self, windows: &[Window]) {
+        let phases = self.detect(windows);
+
+        println!(
+            "{:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}  phase",
+            "start", "accesses", "hits", "misses", "miss rate", "evictions", "blocks"
+        );
+        for (index, window) in windows.iter().enumerate() {
+            // O número da fase aparece só na janela em que ela começa.
+            let phase = phases
+                .iter()
+                .position(|&start| start == index)
+                .map(|phase| format!("* {}", phase))
+                .unwrap_or_default();
+
+            let line = format!(
+                "{:>10} {:>10} {:>10} {:>10} {:>10.4} {:>10} {:>10}  {}",
+                window.start,
+                window.delta.accesses,
+                window.delta.hits,
+                window.delta.misses,
+                window.miss_rate(),
+                window.delta.capacity_misses + window.delta.conflict_misses,
+                window.blocks,
+                phase
+            );
+            println!("{}", line.trim_end());
+        }
+    }
+}
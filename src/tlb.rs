@@ -0,0 +1,215 @@
+use crate::cache::{Cache, Kind, Performance, ReplacementPolicy};
+use rand::Rng;
+
+// Tradução de endereços: os traces têm endereços virtuais, e com ela a cache
+// é indexada pelo endereço físico da página para onde cada página virtual foi
+// mapeada. Cada acesso passa antes por uma TLB, e cada miss dela custa uma
+// busca na tabela de páginas.
+
+// Como as páginas virtuais ganham páginas físicas, na ordem em que aparecem.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Allocation {
+    // A próxima página física livre.
+    Sequential,
+    // Uma página física livre qualquer, da seed da simulação.
+    Random,
+    // A próxima página física livre da mesma cor da virtual: as duas caem nos
+    // mesmos conjuntos da cache, então ela fica como se fosse indexada pelo
+    // endereço virtual.
+    Coloured,
+}
+
+impl std::fmt::Display for Allocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Allocation::Sequential => write!(f, "sequential"),
+            Allocation::Random => write!(f, "random"),
+            Allocation::Coloured => write!(f, "coloured"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Translation {
+    // Em bytes, potência de 2 e pelo menos o tamanho do bloco.
+    pub page_size: usize,
+    pub allocation: Allocation,
+    pub entries: usize,
+    pub assoc: usize,
+    pub repl: ReplacementPolicy,
+    // Ciclos de cada busca na tabela de páginas.
+    pub walk_cycles: usize,
+}
+
+// A TLB é uma cache de números de página, com uma página por bloco.
+#[derive(Debug)]
+pub struct Mmu {
+    pub translation: Translation,
+    tlb: Cache,
+    pages: std::collections::HashMap<u64, u64>,
+    used_frames: std::collections::HashSet<u64>,
+    // Próxima página física de Sequential e de cada cor em Coloured.
+    next_frame: u64,
+    next_by_colour: Vec<u64>,
+    frames: u64,
+    colours: u64,
+    page_bits: u32,
+    rng: rand::rngs::StdRng,
+}
+
+impl Mmu {
+    // `colours` é quantas páginas cabem em uma via da cache, o endereço físico
+    // tem `adress_bits` bits.
+    pub fn new(translation: Translation, colours: usize, adress_bits: usize, seed: u64) -> Mmu {
+        let page_bits = translation.page_size.trailing_zeros();
+        Mmu {
+            translation,
+            tlb: Cache::create_with_seed(
+                translation.entries / translation.assoc,
+                translation.page_size,
+                translation.repl,
+                translation.assoc,
+                Kind::Data,
+                seed,
+            ),
+            pages: Default::default(),
+            used_frames: Default::default(),
+            next_frame: 0,
+            next_by_colour: vec![0; colours],
+            frames: 1u64
+                .checked_shl(adress_bits as u32 - page_bits)
+                .unwrap_or(u64::MAX),
+            colours: colours as u64,
+            page_bits,
+            rng: rand::SeedableRng::seed_from_u64(seed),
+        }
+    }
+
+    fn allocate(&mut self, page: u64) -> u64 {
+        match self.translation.allocation {
+            Allocation::Sequential => {
+                self.next_frame += 1;
+                self.next_frame - 1
+            }
+            // Não acaba: o trace não tem mais páginas virtuais do que existem
+            // físicas.
+            Allocation::Random => loop {
+                let frame = self.rng.gen_range(0, self.frames);
+                if self.used_frames.insert(frame) {
+                    break frame;
+                }
+            },
+            Allocation::Coloured => {
+                let colour = page % self.colours;
+                let next = &mut self.next_by_colour[colour as usize];
+                *next += 1;
+                (*next - 1) * self.colours + colour
+            }
+        }
+    }
+
+    // Endereço físico de `adress`. Com `counted` false (no aquecimento) a TLB
+    // muda mas os contadores dela não.
+    pub fn translate(&mut self, adress: u64, counted: bool) -> u64 {
+        let page = adress >> self.page_bits;
+        let sets = self.tlb.info.nsets as u64;
+
+        if counted && self.tlb.warming_up {
+            self.tlb.end_warmup();
+        }
+        self.tlb.warming_up = !counted;
+        // Um miss da TLB busca a tradução na tabela de páginas, e a primeira
+        // busca de cada página é quando ela ganha uma página física.
+        self.tlb
+            .access_with((page % sets) as usize, (page / sets) as usize, 0);
+
+        let frame = match self.pages.get(&page) {
+            Some(&frame) => frame,
+            None => {
+                let frame = self.allocate(page);
+                self.pages.insert(page, frame);
+                frame
+            }
+        };
+
+        frame << self.page_bits | (adress & ((1 << self.page_bits) - 1))
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            translation: self.translation,
+            tlb: self.tlb.performance.clone(),
+            pages: self.pages.len(),
+            colours: self.colours as usize,
+            comparison: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Stats {
+    pub translation: Translation,
+    // Contadores da TLB, cada miss é uma busca na tabela de páginas.
+    pub tlb: Performance,
+    // Páginas virtuais tocadas.
+    pub pages: usize,
+    pub colours: usize,
+    // Só com `--tlb-compare`, que refaz a simulação duas vezes.
+    pub comparison: Option<Comparison>,
+}
+
+// Misses de conflito da mesma cache indexada pelo endereço virtual, e com
+// páginas coloridas (ou aleatórias, se a simulação já usou cores).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    pub virtual_conflicts: usize,
+    pub allocation: Allocation,
+    pub conflicts: usize,
+}
+
+impl Stats {
+    pub fn walk_cycles(&self) -> usize {
+        self.tlb.misses * self.translation.walk_cycles
+    }
+
+    pub fn format(&self, conflicts: usize) -> String {
+        let rate = |numerator, denominator| {
+            crate::report::rate(numerator, denominator)
+                .map(|rate| format!("{:.4}", rate))
+                .unwrap_or_else(|| "-".to_owned())
+        };
+        let translation = &self.translation;
+
+        format!(
+            concat!(
+                "translation: {} byte pages, {} allocation, {} colours\n",
+                "tlb                 = {} entries, {}-way, {}\n",
+                "tlb accesses        = {}\n",
+                "tlb hit rate        = {}\n",
+                "page walks          = {}\n",
+                "walk cycles         = {} ({} per access)\n",
+                "pages               = {}\n",
+                "conflict misses     = {}{}"
+            ),
+            translation.page_size,
+            translation.allocation,
+            self.colours,
+            translation.entries,
+            translation.assoc,
+            translation.repl,
+            self.tlb.accesses,
+            rate(self.tlb.hits, self.tlb.accesses),
+            self.tlb.misses,
+            self.walk_cycles(),
+            rate(self.walk_cycles(), self.tlb.accesses),
+            self.pages,
+            conflicts,
+            self.comparison
+                .map(|comparison| format!(
+                    " (virtual: {}, {}: {})",
+                    comparison.virtual_conflicts, comparison.allocation, comparison.conflicts
+                ))
+                .unwrap_or_default()
+        )
+    }
+}
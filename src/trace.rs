@@ -0,0 +1,519 @@
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Encoding {
+    // Endereços crus, um depois do outro, com a largura e ordem de bytes do Format.
+    Binary,
+    // Um endereço por linha, em decimal ou em hexadecimal começando com 0x.
+    Text,
+    // Formato do dinero: "<label> <endereço em hex> [tamanho]" por linha.
+    Din,
+    // Formato binário próprio, guarda o tipo, o tamanho e a diferença para o
+    // endereço anterior em varints.
+    Compact,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Endianness {
+    Big,
+    Little,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RecordWidth {
+    W16,
+    W32,
+    W64,
+}
+
+impl RecordWidth {
+    pub fn bytes(self) -> usize {
+        match self {
+            RecordWidth::W16 => 2,
+            RecordWidth::W32 => 4,
+            RecordWidth::W64 => 8,
+        }
+    }
+
+    pub fn bits(self) -> usize {
+        self.bytes() * 8
+    }
+}
+
+// O que fazer quando o tamanho do arquivo não é múltiplo do tamanho do registro.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TrailingBytes {
+    Reject,
+    Truncate,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Format {
+    pub encoding: Encoding,
+    pub endianness: Endianness,
+    pub width: RecordWidth,
+    pub trailing: TrailingBytes,
+    // Tamanho, em bytes, dado aos acessos que não trazem o próprio tamanho.
+    pub access_size: usize,
+}
+
+impl Default for Format {
+    // O formato original do trabalho: u32 big-endian.
+    fn default() -> Format {
+        Format {
+            encoding: Encoding::Binary,
+            endianness: Endianness::Big,
+            width: RecordWidth::W32,
+            trailing: TrailingBytes::Reject,
+            access_size: 1,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AccessType {
+    Read,
+    Write,
+    Fetch,
+}
+
+impl AccessType {
+    // Os labels usados no formato din e também no formato compacto.
+    fn label(self) -> u8 {
+        match self {
+            AccessType::Read => 0,
+            AccessType::Write => 1,
+            AccessType::Fetch => 2,
+        }
+    }
+
+    fn from_label(label: u8) -> Option<AccessType> {
+        match label {
+            0 => Some(AccessType::Read),
+            1 => Some(AccessType::Write),
+            2 => Some(AccessType::Fetch),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Record {
+    pub adress: u64,
+    // Quantos bytes são acessados a partir de adress.
+    pub size: usize,
+    pub access: AccessType,
+    // Valor lido ou escrito pelo acesso (os `size` bytes a partir de adress,
+    // little-endian), só quando o trace traz valores.
+    pub value: Option<u64>,
+}
+
+impl Record {
+    // Números dos blocos (endereço sem os nbits_offset bits de offset) tocados
+    // pelo acesso, do primeiro ao último.
+    pub fn blocks(&self, nbits_offset: usize) -> std::ops::RangeInclusive<u64> {
+        let last_byte = self.adress.saturating_add(self.size.max(1) as u64 - 1);
+        (self.adress >> nbits_offset)..=(last_byte >> nbits_offset)
+    }
+}
+
+pub struct Filter {
+    // Vazio deixa passar todos os tipos de acesso.
+    pub accesses: Vec<AccessType>,
+    // Os dois limites são inclusivos.
+    pub min_adress: u64,
+    pub max_adress: u64,
+}
+
+impl Default for Filter {
+    fn default() -> Filter {
+        Filter {
+            accesses: Vec::new(),
+            min_adress: 0,
+            max_adress: u64::MAX,
+        }
+    }
+}
+
+impl Filter {
+    pub fn keeps(&self, record: &Record) -> bool {
+        (self.accesses.is_empty() || self.accesses.contains(&record.access))
+            && record.adress >= self.min_adress
+            && record.adress <= self.max_adress
+    }
+}
+
+pub fn decode(raw_data: &[u8], format: &Format) -> Result<Vec<u64>, String> {
+    let record_bytes = format.width.bytes();
+    let records = raw_data.chunks_exact(record_bytes);
+
+    let leftover = records.remainder().len();
+    if leftover != 0 {
+        match format.trailing {
+            TrailingBytes::Reject => Err(format!(
+                "Input ends with a partial record: {} bytes is not a multiple of the {}-byte \
+                 record width ({} leftover bytes at offset {})",
+                raw_data.len(),
+                record_bytes,
+                leftover,
+                raw_data.len() - leftover
+            ))?,
+            TrailingBytes::Truncate => eprintln!(
+                "Warning: ignoring {} trailing bytes at offset {} (partial {}-byte record)",
+                leftover,
+                raw_data.len() - leftover,
+                record_bytes
+            ),
+        }
+    }
+
+    Ok(records
+        .map(|record| match format.endianness {
+            Endianness::Big => record
+                .iter()
+                .fold(0u64, |acc, &byte| (acc << 8) | u64::from(byte)),
+            Endianness::Little => record
+                .iter()
+                .rev()
+                .fold(0u64, |acc, &byte| (acc << 8) | u64::from(byte)),
+        })
+        .collect())
+}
+
+const COMPACT_MAGIC: &[u8] = b"CSIM";
+const COMPACT_VERSION: u8 = 1;
+// Bit do cabeçalho de cada registro do formato compacto que diz que o tamanho
+// vem depois do endereço (se não vier o tamanho é 1).
+const COMPACT_HAS_SIZE: u8 = 0b100;
+// E esse diz que o valor vem depois do tamanho.
+const COMPACT_HAS_VALUE: u8 = 0b1000;
+
+fn parse_number(token: &str) -> Option<u64> {
+    if token.starts_with("0x") || token.starts_with("0X") {
+        u64::from_str_radix(&token[2..], 16).ok()
+    } else {
+        str::parse::<u64>(token).ok()
+    }
+}
+
+fn text_lines(raw_data: &[u8]) -> Result<impl Iterator<Item = (usize, &str)>, String> {
+    let text = std::str::from_utf8(raw_data).map_err(|e| format!("{:#?}", e))?;
+
+    // Linhas vazias e começando com '#' são ignoradas, a numeração começa em 1
+    // para as mensagens de erro.
+    Ok(text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#')))
+}
+
+fn parse_text(raw_data: &[u8], format: &Format) -> Result<Vec<Record>, String> {
+    text_lines(raw_data)?
+        .map(|(line_number, line)| {
+            let adress = parse_number(line).ok_or_else(|| {
+                format!(
+                    "Line {}: cannot convert '{}' into an adress",
+                    line_number, line
+                )
+            })?;
+
+            Ok(Record {
+                adress,
+                size: format.access_size,
+                access: AccessType::Read,
+                value: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_din(raw_data: &[u8], format: &Format) -> Result<Vec<Record>, String> {
+    text_lines(raw_data)?
+        .map(|(line_number, line)| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 2 || fields.len() > 4 {
+                Err(format!(
+                    "Line {}: expected '<label> <adress> [size] [value]' but got '{}'",
+                    line_number, line
+                ))?
+            }
+
+            let access = str::parse::<u8>(fields[0])
+                .ok()
+                .and_then(AccessType::from_label)
+                .ok_or_else(|| {
+                    format!(
+                        "Line {}: din label '{}' is not supported (only 0, 1 and 2 are)",
+                        line_number, fields[0]
+                    )
+                })?;
+            let hex = fields[1].trim_start_matches("0x").trim_start_matches("0X");
+            let adress = u64::from_str_radix(hex, 16).map_err(|_| {
+                format!(
+                    "Line {}: cannot convert '{}' into an hexadecimal adress",
+                    line_number, fields[1]
+                )
+            })?;
+            let size = match fields.get(2) {
+                Some(size) => str::parse::<usize>(size).map_err(|_| {
+                    format!(
+                        "Line {}: cannot convert '{}' into a size",
+                        line_number, size
+                    )
+                })?,
+                None => format.access_size,
+            };
+            // Extensão nossa do din: o valor em hexadecimal depois do tamanho.
+            let value = match fields.get(3) {
+                Some(value) => Some(
+                    u64::from_str_radix(value.trim_start_matches("0x"), 16).map_err(|_| {
+                        format!(
+                            "Line {}: cannot convert '{}' into an hexadecimal value",
+                            line_number, value
+                        )
+                    })?,
+                ),
+                None => None,
+            };
+
+            Ok(Record {
+                adress,
+                size,
+                access,
+                value,
+            })
+        })
+        .collect()
+}
+
+fn read_varint(raw_data: &[u8], position: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *raw_data
+            .get(*position)
+            .ok_or_else(|| "Compact trace ends in the middle of a record".to_owned())?;
+        *position += 1;
+
+        if shift >= 64 {
+            Err(format!(
+                "Compact trace has a malformed varint at offset {}",
+                *position - 1
+            ))?
+        }
+        value |= u64::from(byte & 0x7f) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+fn parse_compact(raw_data: &[u8]) -> Result<Vec<Record>, String> {
+    if !raw_data.starts_with(COMPACT_MAGIC) || raw_data.len() < COMPACT_MAGIC.len() + 1 {
+        Err("Input is not a compact trace (missing header)".to_owned())?
+    }
+    let version = raw_data[COMPACT_MAGIC.len()];
+    if version != COMPACT_VERSION {
+        Err(format!(
+            "Compact trace version {} is not supported (expected {})",
+            version, COMPACT_VERSION
+        ))?
+    }
+
+    let mut records = Vec::new();
+    let mut position = COMPACT_MAGIC.len() + 1;
+    let mut previous = 0u64;
+    while position < raw_data.len() {
+        let header = raw_data[position];
+        position += 1;
+
+        let access = AccessType::from_label(header & 0b11).ok_or_else(|| {
+            format!(
+                "Compact trace has an unknown access type at offset {}",
+                position - 1
+            )
+        })?;
+
+        // A diferença é guardada em zigzag para que deltas negativos pequenos
+        // também ocupem poucos bytes.
+        let zigzag = read_varint(raw_data, &mut position)?;
+        let delta = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+        let adress = previous.wrapping_add(delta as u64);
+        previous = adress;
+
+        let size = if header & COMPACT_HAS_SIZE != 0 {
+            read_varint(raw_data, &mut position)? as usize
+        } else {
+            1
+        };
+        let value = if header & COMPACT_HAS_VALUE != 0 {
+            Some(read_varint(raw_data, &mut position)?)
+        } else {
+            None
+        };
+
+        records.push(Record {
+            adress,
+            size,
+            access,
+            value,
+        });
+    }
+
+    Ok(records)
+}
+
+fn max_adress(width: RecordWidth) -> u64 {
+    u64::MAX >> (64 - width.bits())
+}
+
+pub fn parse(raw_data: &[u8], format: &Format) -> Result<Vec<Record>, String> {
+    let records = match format.encoding {
+        Encoding::Binary => decode(raw_data, format)?
+            .into_iter()
+            .map(|adress| Record {
+                adress,
+                size: format.access_size,
+                access: AccessType::Read,
+                value: None,
+            })
+            .collect(),
+        Encoding::Text => parse_text(raw_data, format)?,
+        Encoding::Din => parse_din(raw_data, format)?,
+        Encoding::Compact => parse_compact(raw_data)?,
+    };
+
+    if let Some((position, record)) = records
+        .iter()
+        .enumerate()
+        .find(|(_, record)| record.adress > max_adress(format.width))
+    {
+        Err(format!(
+            "Record {} has adress {:#x}, which does not fit in {}-bit adresses",
+            position,
+            record.adress,
+            format.width.bits()
+        ))?
+    }
+
+    Ok(records)
+}
+
+pub fn encode(records: &[Record], format: &Format) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+
+    match format.encoding {
+        Encoding::Binary => {
+            let record_bytes = format.width.bytes();
+            for (position, record) in records.iter().enumerate() {
+                if record.adress > max_adress(format.width) {
+                    Err(format!(
+                        "Record {} has adress {:#x}, which does not fit in {}-bit records",
+                        position,
+                        record.adress,
+                        format.width.bits()
+                    ))?
+                }
+
+                match format.endianness {
+                    Endianness::Big => {
+                        output.extend_from_slice(&record.adress.to_be_bytes()[8 - record_bytes..])
+                    }
+                    Endianness::Little => {
+                        output.extend_from_slice(&record.adress.to_le_bytes()[..record_bytes])
+                    }
+                }
+            }
+        }
+        Encoding::Text => {
+            for record in records {
+                output.extend_from_slice(format!("{}\n", record.adress).as_bytes());
+            }
+        }
+        Encoding::Din => {
+            for record in records {
+                // O tamanho só é escrito quando foge do padrão (ou quando tem
+                // valor), assim a saída continua sendo din puro para acessos
+                // de 1 byte.
+                let line = match record.value {
+                    Some(value) => format!(
+                        "{} {:x} {} {:x}\n",
+                        record.access.label(),
+                        record.adress,
+                        record.size,
+                        value
+                    ),
+                    None if record.size == 1 => {
+                        format!("{} {:x}\n", record.access.label(), record.adress)
+                    }
+                    None => format!(
+                        "{} {:x} {}\n",
+                        record.access.label(),
+                        record.adress,
+                        record.size
+                    ),
+                };
+                output.extend_from_slice(line.as_bytes());
+            }
+        }
+        Encoding::Compact => {
+            output.extend_from_slice(COMPACT_MAGIC);
+            output.push(COMPACT_VERSION);
+
+            let mut previous = 0u64;
+            for record in records {
+                let has_size = record.size != 1;
+                output.push(
+                    record.access.label()
+                        | if has_size { COMPACT_HAS_SIZE } else { 0 }
+                        | if record.value.is_some() {
+                            COMPACT_HAS_VALUE
+                        } else {
+                            0
+                        },
+                );
+
+                let delta = record.adress.wrapping_sub(previous) as i64;
+                write_varint(&mut output, ((delta << 1) ^ (delta >> 63)) as u64);
+                previous = record.adress;
+
+                if has_size {
+                    write_varint(&mut output, record.size as u64);
+                }
+                if let Some(value) = record.value {
+                    write_varint(&mut output, value);
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+pub fn readfile(filename: &str, format: &Format) -> Result<Vec<Record>, String> {
+    let raw_data: Vec<u8> = std::fs::read(filename).map_err(|e| format!("{:#?}", e))?;
+    parse(&raw_data, format)
+}
+
+pub fn writefile(filename: &str, records: &[Record], format: &Format) -> Result<(), String> {
+    std::fs::write(filename, encode(records, format)?).map_err(|e| format!("{:#?}", e))
+}
+
+#[test]
+#[ignore]
+// Só deve rodar se os arquivos estiverem presente.
+fn readfile_test() {
+    let format = Format::default();
+    readfile("testfiles/bin_100.bin", &format).unwrap();
+    readfile("testfiles/bin_1000.bin", &format).unwrap();
+    readfile("testfiles/bin_10000.bin", &format).unwrap();
+}
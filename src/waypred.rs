@@ -0,0 +1,126 @@
+// Previsão de via: antes de olhar o conjunto inteiro a cache lê só a via
+// prevista, e só quando ela erra (ou é miss) lê todas as vias.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Predictor {
+    // A via usada por último em cada conjunto.
+    Mru,
+    // Uma tabela de `entries` vias indexada pelo PC. Os traces não têm PC,
+    // então ele é o endereço do último acesso do tipo Fetch (0 antes do
+    // primeiro), e sem Fetch no trace a tabela vira uma entrada só. O fetch
+    // e os acessos a dados depois dele usam entradas diferentes.
+    Pc { entries: usize },
+}
+
+impl std::fmt::Display for Predictor {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Predictor::Mru => write!(f, "mru"),
+            Predictor::Pc { entries } => write!(f, "pc ({} entries)", entries),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct WayPredictor {
+    pub predictor: Predictor,
+    table: Vec<usize>,
+    // Hits em que a via prevista era a certa.
+    pub correct: usize,
+    // Hits em outra via, que precisaram da segunda leitura.
+    pub mispredicted: usize,
+    pub misses: usize,
+}
+
+impl WayPredictor {
+    pub fn new(predictor: Predictor, nsets: usize) -> WayPredictor {
+        let entries = match predictor {
+            Predictor::Mru => nsets,
+            Predictor::Pc { entries } => entries,
+        };
+
+        WayPredictor {
+            predictor,
+            table: vec![0; entries],
+            correct: 0,
+            mispredicted: 0,
+            misses: 0,
+        }
+    }
+
+    fn entry(&self, index: usize, pc: u64) -> usize {
+        match self.predictor {
+            Predictor::Mru => index,
+            Predictor::Pc { entries } => (pc % entries as u64) as usize,
+        }
+    }
+
+    pub fn predict(&self, index: usize, pc: u64) -> usize {
+        self.table[self.entry(index, pc)]
+    }
+
+    // `hit_way` é a via que tinha a tag antes do acesso (None num miss) e
+    // `way` a via usada por ele, que treina a tabela. Com `counted` false (no
+    // aquecimento) só a tabela muda.
+    pub fn record(
+        &mut self,
+        index: usize,
+        pc: u64,
+        hit_way: Option<usize>,
+        way: usize,
+        counted: bool,
+    ) {
+        if counted {
+            match hit_way {
+                Some(hit_way) if hit_way == self.predict(index, pc) => self.correct += 1,
+                Some(_) => self.mispredicted += 1,
+                None => self.misses += 1,
+            }
+        }
+
+        let entry = self.entry(index, pc);
+        self.table[entry] = way;
+    }
+
+    pub fn lookups(&self) -> usize {
+        self.correct + self.mispredicted + self.misses
+    }
+
+    // Energia das buscas em leituras de uma via (tag e dados). Um acerto da
+    // previsão lê uma via, um erro ou um miss lê a via prevista e depois o
+    // conjunto inteiro.
+    pub fn probes(&self, assoc: usize) -> usize {
+        self.correct + (self.mispredicted + self.misses) * (1 + assoc)
+    }
+
+    // A mesma energia sem previsão, com as `assoc` vias lidas em paralelo.
+    pub fn parallel_probes(&self, assoc: usize) -> usize {
+        self.lookups() * assoc
+    }
+
+    pub fn format(&self, assoc: usize) -> String {
+        let rate = |numerator, denominator| {
+            crate::report::rate(numerator, denominator)
+                .map(|rate| format!("{:.4}", rate))
+                .unwrap_or_else(|| "-".to_owned())
+        };
+
+        format!(
+            concat!(
+                "way prediction: {}\n",
+                "correct             = {}\n",
+                "mispredicted        = {}\n",
+                "misses              = {}\n",
+                "accuracy            = {}\n",
+                "way probes          = {} (parallel lookup: {}, ratio {})"
+            ),
+            self.predictor,
+            self.correct,
+            self.mispredicted,
+            self.misses,
+            rate(self.correct, self.correct + self.mispredicted),
+            self.probes(assoc),
+            self.parallel_probes(assoc),
+            rate(self.probes(assoc), self.parallel_probes(assoc))
+        )
+    }
+}
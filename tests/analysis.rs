@@ -0,0 +1,56 @@
+mod common;
+
+#[test]
+fn reuse_profile_test() {
+    use csimlib::analysis::ReuseProfile;
+
+    // Blocos de 4 bytes: a b c a a b
+    let input = common::reads(vec![0, 4, 8, 0, 1, 5]);
+
+    let profile = ReuseProfile::compute(&input, 4);
+    assert_eq!(profile.references, 6);
+    assert_eq!(profile.cold, 3);
+    assert_eq!(profile.histogram, vec![1, 0, 2]);
+    assert_eq!(profile.misses(1), 5);
+    assert_eq!(profile.misses(2), 5);
+    assert_eq!(profile.misses(3), 3);
+}
+
+#[test]
+fn reuse_profile_matches_fully_associative_lru_test() {
+    use csimlib::analysis::ReuseProfile;
+
+    let input = std::sync::Arc::new(common::random_reads(2000, 256, 7));
+    let profile = ReuseProfile::compute(&input, 4);
+
+    for &assoc in &[1, 2, 4, 8, 16, 32, 64] {
+        let params = common::params(1, 4, assoc, input.clone());
+
+        let perf = csimlib::run_with(&params).performance;
+        assert_eq!(profile.misses(assoc), perf.misses, "assoc = {}", assoc);
+    }
+}
+
+#[test]
+fn stack_grid_matches_run_with_test() {
+    use csimlib::stacksim::StackGrid;
+
+    let input = std::sync::Arc::new(common::random_reads(3000, 1024, 11));
+    let nsets = [1, 4, 16, 64];
+    let assocs = [1, 2, 4, 8];
+    let grid = StackGrid::simulate(&input, 8, &nsets, &assocs);
+
+    assert_eq!(grid.references, input.len());
+    for (i, &sets) in nsets.iter().enumerate() {
+        for (j, &assoc) in assocs.iter().enumerate() {
+            let params = common::params(sets, 8, assoc, input.clone());
+
+            let perf = csimlib::run_with(&params).performance;
+            assert_eq!(
+                grid.misses[i][j], perf.misses,
+                "nsets = {}, assoc = {}",
+                sets, assoc
+            );
+        }
+    }
+}
@@ -1,3 +1,5 @@
+mod common;
+
 #[test]
 fn cache_create_test() {
     use csimlib::cache;
@@ -19,6 +21,48 @@ fn cache_create_test() {
     assert_eq!(cache.info.repl, repl);
 }
 
+#[test]
+fn victim_test() {
+    use csimlib::cache::{Cache, Kind, ReplacementPolicy};
+
+    // O Lru tira a via usada há mais tempo e o Fifo a que entrou primeiro.
+    for &(repl, resident) in &[
+        (ReplacementPolicy::Lru, [1, 3, 4]),
+        (ReplacementPolicy::Fifo, [2, 3, 4]),
+    ] {
+        let mut cache = Cache::create(2, 4, repl, 3, Kind::Data);
+        for &tag in &[1, 2, 3, 1, 4] {
+            cache.access_with(0, tag, 0);
+        }
+
+        let mut tags: Vec<usize> = cache.data[0].iter().map(|way| way.tag).collect();
+        tags.sort_unstable();
+        assert_eq!(tags, resident, "{:?}", repl);
+    }
+}
+
+#[test]
+fn vacated_way_test() {
+    use csimlib::cache::{Cache, Kind, ReplacementPolicy};
+
+    // Uma via vazia que guardou a tag 0 não é a tag 0: o hit tem que atualizar
+    // a via válida, senão o Lru tira a 0 no lugar da 7.
+    let mut cache = Cache::create(2, 4, ReplacementPolicy::Lru, 3, Kind::Data);
+    for &tag in &[3, 0, 7] {
+        cache.access_with(0, tag, 0);
+    }
+    cache.data[0][0].is_initialized = false;
+    cache.data[0][0].tag = 0;
+
+    for &tag in &[0, 8, 9] {
+        cache.access_with(0, tag, 0);
+    }
+
+    let mut tags: Vec<usize> = cache.data[0].iter().map(|way| way.tag).collect();
+    tags.sort_unstable();
+    assert_eq!(tags, [0, 8, 9]);
+}
+
 #[test]
 #[ignore]
 // Só deve rodar se os arquivos estiverem presente.
@@ -44,9 +88,173 @@ fn control_tests() {
             args[2],
             args[3],
             args[4],
-            csimlib::Either::Left(args[5]),
+            csimlib::Either::Left((args[5], Default::default())),
         )
         .unwrap();
         csimlib::run_with(&params).print_perf(params.verbosity);
     }
 }
+
+#[test]
+fn run_with_split_test() {
+    use csimlib::trace::{AccessType, Record};
+
+    let params = common::params(
+        4,
+        4,
+        1,
+        vec![
+            // Cruza do bloco 0 para o bloco 1.
+            Record {
+                adress: 2,
+                size: 4,
+                access: AccessType::Read,
+                value: None,
+            },
+            Record {
+                adress: 4,
+                size: 1,
+                access: AccessType::Read,
+                value: None,
+            },
+            // Cabe certinho no bloco 2.
+            Record {
+                adress: 8,
+                size: 4,
+                access: AccessType::Read,
+                value: None,
+            },
+        ],
+    );
+
+    let perf = csimlib::run_with(&params).performance;
+    assert_eq!(perf.split_accesses, 1);
+    assert_eq!(perf.accesses, 4);
+    assert_eq!(perf.hits, 1);
+    assert_eq!(perf.misses, 3);
+}
+
+#[test]
+fn set_performance_test() {
+    use csimlib::cache;
+
+    let mut cache = cache::Cache::create(2, 4, cache::ReplacementPolicy::Lru, 1, cache::Kind::Both);
+    cache.access_with(0, 1, 0);
+    cache.access_with(0, 1, 0);
+    cache.access_with(0, 2, 0);
+    cache.access_with(1, 1, 0);
+
+    let set0 = &cache.set_performance[0];
+    assert_eq!((set0.hits, set0.misses, set0.evictions), (1, 2, 1));
+    let set1 = &cache.set_performance[1];
+    assert_eq!((set1.hits, set1.misses, set1.evictions), (0, 1, 0));
+}
+
+#[test]
+fn warmup_test() {
+    let mut params = common::params(4, 4, 1, common::reads(vec![0, 4, 0, 4, 8, 0]));
+    params.options.warmup = Some(csimlib::Warmup::Accesses(2));
+
+    // Os dois primeiros misses compulsórios ficam no aquecimento.
+    let cache = csimlib::run_with(&params);
+    assert_eq!(cache.warmup_performance.accesses, 2);
+    assert_eq!(cache.warmup_performance.compulsory_misses, 2);
+    assert_eq!(cache.performance.accesses, 4);
+    assert_eq!(cache.performance.hits, 3);
+    assert_eq!(cache.performance.compulsory_misses, 1);
+    assert_eq!(cache.performance.slots_occupied, 3);
+    assert_eq!(cache.set_performance[0].hits, 2);
+    assert_eq!(cache.set_performance[1].misses, 0);
+
+    params.options.warmup = Some(csimlib::parse_warmup("50%").unwrap());
+    let cache = csimlib::run_with(&params);
+    assert_eq!(cache.warmup_performance.accesses, 3);
+    assert_eq!(cache.performance.accesses, 3);
+
+    assert_eq!(
+        csimlib::parse_warmup("0.25"),
+        Ok(csimlib::Warmup::Fraction(0.25))
+    );
+    assert!(csimlib::parse_warmup("150%").is_err());
+}
+
+#[test]
+fn fully_associative_test() {
+    use csimlib::cache::{Cache, Kind, ReplacementPolicy};
+    use rand::Rng;
+
+    let params = csimlib::parse_and_validate(
+        "64",
+        "4",
+        "full",
+        "l",
+        "1",
+        csimlib::Either::Right(("10", "1".to_owned())),
+    )
+    .unwrap();
+    assert_eq!((params.nsets, params.assoc), (1, 64));
+
+    // O caminho rápido (tag_map) tem que dar os mesmos resultados que o
+    // caminho normal do Conjunto, com as mesmas vítimas do Random.
+    for &repl in &[
+        ReplacementPolicy::Lru,
+        ReplacementPolicy::Fifo,
+        ReplacementPolicy::Random,
+    ] {
+        let mut fast = Cache::create_with_seed(1, 4, repl, 16, Kind::Both, 9);
+        let mut linear = Cache::create_with_seed(1, 4, repl, 16, Kind::Both, 9);
+        assert!(fast.tag_map.is_some());
+        linear.tag_map = None;
+
+        let mut rng: rand::rngs::StdRng = rand::SeedableRng::seed_from_u64(2);
+        for _ in 0..5000 {
+            let tag = rng.gen_range(0, 24);
+            assert_eq!(fast.access_with(0, tag, 0), linear.access_with(0, tag, 0));
+        }
+        assert_eq!(fast.performance.misses, linear.performance.misses);
+        assert_eq!(
+            fast.performance.capacity_misses,
+            linear.performance.capacity_misses
+        );
+
+        // As vias também são as mesmas.
+        let tags = |cache: &Cache| {
+            cache.data[0]
+                .iter()
+                .map(|line| line.tag)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tags(&fast), tags(&linear));
+    }
+}
+
+#[test]
+fn total_size_test() {
+    let parse = |nsets, assoc| {
+        csimlib::parse_and_validate(
+            nsets,
+            "64",
+            assoc,
+            "l",
+            "1",
+            csimlib::Either::Right(("10", "1".to_owned())),
+        )
+        .map(|params| (params.nsets, params.assoc))
+    };
+
+    assert_eq!(parse("32KiB", "8"), Ok((64, 8)));
+    assert_eq!(parse("32k", "8"), Ok((64, 8)));
+    assert_eq!(parse("1M", "16"), Ok((1024, 16)));
+    assert_eq!(parse("4K", "full"), Ok((1, 64)));
+    // 96 conjuntos, que usam o índice por módulo.
+    assert_eq!(parse("24KiB", "4"), Ok((96, 4)));
+    // Só bytes pareceria a quantidade de conjuntos.
+    assert!(parse("4096B", "full").is_err());
+    assert!(parse("4096b", "2").is_err());
+    assert!(parse("1K", "32").unwrap_err().contains("total cache size"));
+    assert!(parse("32KB", "1024").is_err());
+    assert!(parse("32Q", "8").is_err());
+
+    assert_eq!(csimlib::parse_size("size", "2MiB"), Ok(2 << 20));
+    assert!(csimlib::parse_size("size", "KiB").is_err());
+}
@@ -0,0 +1,56 @@
+// Traces e parâmetros usados pelos testes, cada arquivo de teste usa só uma
+// parte deles.
+#![allow(dead_code)]
+
+use csimlib::trace::{AccessType, Record};
+use std::sync::Arc;
+
+// Acessos de 1 byte do tipo dado em cada endereço, sem valor.
+pub fn accesses(records: impl IntoIterator<Item = (AccessType, u64)>) -> Vec<Record> {
+    records
+        .into_iter()
+        .map(|(access, adress)| Record {
+            adress,
+            size: 1,
+            access,
+            value: None,
+        })
+        .collect()
+}
+
+// Leituras de 1 byte em cada endereço.
+pub fn reads(adresses: impl IntoIterator<Item = u64>) -> Vec<Record> {
+    accesses(
+        adresses
+            .into_iter()
+            .map(|adress| (AccessType::Read, adress)),
+    )
+}
+
+// `len` leituras em endereços aleatórios menores que `adresses`.
+pub fn random_reads(len: usize, adresses: u32, seed: u64) -> Vec<Record> {
+    use rand::Rng;
+
+    let mut rng: rand::rngs::StdRng = rand::SeedableRng::seed_from_u64(seed);
+    reads((0..len).map(|_| u64::from(rng.gen_range(0, adresses))))
+}
+
+// Uma simulação Lru com endereços de 32 bits e as opções padrão. O resto muda
+// com `RunParams { .., ..params(..) }` ou em `options`.
+pub fn params(
+    nsets: usize,
+    bsize: usize,
+    assoc: usize,
+    input: impl Into<Arc<Vec<Record>>>,
+) -> csimlib::RunParams {
+    csimlib::RunParams {
+        nsets,
+        bsize,
+        assoc,
+        repl: csimlib::cache::ReplacementPolicy::Lru,
+        verbosity: 1,
+        adress_bits: 32,
+        input: input.into(),
+        options: Default::default(),
+    }
+}
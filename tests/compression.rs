@@ -0,0 +1,144 @@
+mod common;
+
+#[test]
+fn line_size_test() {
+    use csimlib::compression::{line_size, Algorithm};
+
+    let words = |values: &[u64]| -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes().to_vec())
+            .collect()
+    };
+
+    for &algorithm in &[Algorithm::Bdi, Algorithm::Fpc] {
+        // Uma linha zerada ocupa um segmento só.
+        assert_eq!(line_size(algorithm, &[0; 64]), 8);
+        // Bytes aleatórios não comprimem.
+        let random: Vec<u8> = (0..64u64)
+            .map(|byte| (byte * 97 + 13) as u8 ^ 0xa5)
+            .collect();
+        assert_eq!(line_size(algorithm, &random), 64);
+    }
+
+    // Ponteiros perto uns dos outros: base de 8 bytes, 8 deltas de 1 byte e a
+    // máscara de 1 byte, 17 bytes em 3 segmentos.
+    let pointers: Vec<u64> = (0..8).map(|word| 0x7fff_0000_1000 + word * 8).collect();
+    assert_eq!(line_size(Algorithm::Bdi, &words(&pointers)), 24);
+    // O mesmo valor repetido vira só a base.
+    assert_eq!(line_size(Algorithm::Bdi, &words(&[0x1234_5678_9abc; 8])), 8);
+
+    // Inteiros pequenos de 4 bytes: 16 palavras de 3 + 4 bits no fpc.
+    let small: Vec<u8> = (0..16i32)
+        .flat_map(|word| (word - 8).to_le_bytes().to_vec())
+        .collect();
+    assert_eq!(line_size(Algorithm::Fpc, &small), 16);
+}
+
+#[test]
+fn values_test() {
+    use csimlib::compression::{Memory, Values};
+
+    let mut memory = Memory::default();
+    memory.write(0x13, 4, 0xdead_beef);
+    let line = Values::Trace.line(&memory, 1, 16);
+    assert_eq!(&line[..8], &[0, 0, 0, 0xef, 0xbe, 0xad, 0xde, 0]);
+    assert!(line[8..].iter().all(|&byte| byte == 0));
+
+    // Os dados sintéticos só dependem do número do bloco.
+    let synthetic = Values::Synthetic {
+        zero: 0.5,
+        narrow: 0.5,
+    };
+    assert_eq!(
+        synthetic.line(&memory, 7, 64),
+        synthetic.line(&Memory::default(), 7, 64)
+    );
+    let zeros = Values::Synthetic {
+        zero: 1.0,
+        narrow: 0.0,
+    };
+    assert_eq!(zeros.line(&memory, 1, 16), vec![0; 16]);
+
+    assert!(csimlib::parse_compression("bdi", Some("0.5,0.6")).is_err());
+    assert!(csimlib::parse_compression("fpc", Some("0.5")).is_err());
+}
+
+#[test]
+fn compressed_cache_test() {
+    use csimlib::compression::{Algorithm, Compression, Values};
+
+    // 3 blocos em ciclo num conjunto de 2 vias: sem compressão o Lru erra
+    // todos, comprimidos os 3 cabem e só os primeiros acessos erram.
+    let mut params = common::params(
+        1,
+        16,
+        2,
+        common::reads((0..30).map(|access| access % 3 * 16)),
+    );
+    params.options.compression = Some(Compression {
+        algorithm: Algorithm::Bdi,
+        values: Values::Trace,
+    });
+    let cache = csimlib::run_with(&params);
+    let stats = cache.compression.unwrap();
+
+    assert_eq!(cache.performance.misses, 3);
+    assert_eq!(cache.performance.compulsory_misses, 3);
+    assert_eq!(stats.baseline_hits, 0);
+    assert_eq!(stats.extra_hits(cache.performance.hits), 27);
+    assert_eq!(stats.resident_lines, 3);
+    assert_eq!(stats.ratio(), 2.0);
+
+    // Dados que não comprimem voltam a errar tudo.
+    params.options.compression = Some(Compression {
+        algorithm: Algorithm::Bdi,
+        values: Values::Synthetic {
+            zero: 0.0,
+            narrow: 0.0,
+        },
+    });
+    let cache = csimlib::run_with(&params);
+    assert_eq!(cache.performance.hits, 0);
+    assert_eq!(cache.compression.as_ref().unwrap().resident_lines, 2);
+
+    // A compressão e o modelo de valores entram na configuração.
+    let config = csimlib::sweep::Config::from(&cache.info);
+    assert_eq!(config.compression, params.options.compression);
+    let json = csimlib::report::json(&config, cache.kind, &cache.performance, 0);
+    assert!(json.contains("\"compression\": \"bdi\", \"value_model\": \"0,0\""));
+    let row = csimlib::report::csv_row(&config, &cache.performance, 0, None);
+    assert!(row.contains(",bdi,\"0,0\",,,,,,,"));
+}
+
+#[test]
+fn compressed_rereference_test() {
+    use csimlib::cache::{AccessResult, Cache, Kind, MissTypes, ReplacementPolicy};
+
+    // Uma via de 16 bytes com duas tags: B diminui numa escrita e A volta sem
+    // tirar ninguém, mas A já passou pela cache.
+    let mut cache = Cache::create_with_seed(1, 16, ReplacementPolicy::Lru, 1, Kind::Both, 0);
+    cache.enable_compression();
+    let (a, b) = (1, 2);
+
+    assert_eq!(
+        cache.access_compressed(0, a, 16),
+        AccessResult::Miss(MissTypes::Compulsory)
+    );
+    // B tira A, mas é a primeira vez de B.
+    assert_eq!(
+        cache.access_compressed(0, b, 16),
+        AccessResult::Miss(MissTypes::Compulsory)
+    );
+    assert_eq!(cache.access_compressed(0, b, 8), AccessResult::Hit);
+    assert_eq!(
+        cache.access_compressed(0, a, 8),
+        AccessResult::Miss(MissTypes::Capacity)
+    );
+    assert_eq!(cache.access_compressed(0, a, 8), AccessResult::Hit);
+    assert_eq!(cache.access_compressed(0, b, 8), AccessResult::Hit);
+
+    assert_eq!(cache.performance.compulsory_misses, 2);
+    assert_eq!(cache.performance.capacity_misses, 1);
+    assert_eq!(cache.performance.slots_occupied, cache.info.total_slots);
+}
@@ -0,0 +1,147 @@
+mod common;
+
+#[test]
+fn technology_test() {
+    use csimlib::energy::Technology;
+
+    let technology = Technology::parse("# comentário\n\nbit_read = 0.01\nmiss=50 # L2\n").unwrap();
+    assert_eq!(technology.bit_read, 0.01);
+    assert_eq!(technology.miss, 50.0);
+    assert_eq!(technology.bit_write, Technology::default().bit_write);
+
+    assert!(Technology::parse("bit_read 0.01").is_err());
+    assert!(Technology::parse("bit_read = -1").is_err());
+    assert!(Technology::parse("bit_reed = 1").is_err());
+}
+
+#[test]
+fn estimate_test() {
+    use csimlib::energy::Technology;
+
+    let technology = Technology {
+        bit_read: 1.0,
+        bit_write: 2.0,
+        bitline_per_row: 0.0,
+        tag_compare: 0.5,
+        miss: 10.0,
+        leakage_per_bit: 1000.0,
+        cycle_time: 1.0,
+        hit_cycles: 1.0,
+        miss_cycles: 10.0,
+        cell_area: 1.0,
+        area_overhead: 2.0,
+    };
+    let mut params = csimlib::RunParams {
+        adress_bits: 16,
+        ..common::params(4, 4, 2, common::reads(vec![0, 0, 0, 64]))
+    };
+    params.options.technology = Some(technology);
+    let cache = csimlib::run_with(&params);
+    let energy = cache.energy.unwrap();
+
+    // 16 - 2 - 2 bits de tag, 32 de dados e válido e sujo.
+    assert_eq!(energy.tag_bits, 12);
+    assert_eq!(energy.line_bits, 46);
+    assert_eq!(energy.total_bits, 46 * 8);
+    assert_eq!(energy.way_read, 46.0 + 6.0);
+    // 4 acessos lendo as 2 vias e 2 misses escrevendo a linha.
+    assert_eq!(energy.way_probes, 8);
+    assert_eq!(energy.dynamic, 8.0 * 52.0 + 2.0 * (92.0 + 10.0));
+    // 368 bits * 1000 nW durante 2 + 20 ns.
+    assert!((energy.leakage - 368.0 * 1000.0 * 22.0 * 1e-6).abs() < 1e-9);
+    assert!((energy.area - 368.0 * 2.0 * 1e-6).abs() < 1e-12);
+
+    // Com previsão de via o modelo usa as leituras do WayPredictor.
+    params.options.way_predictor = Some(csimlib::waypred::Predictor::Mru);
+    let cache = csimlib::run_with(&params);
+    assert_eq!(cache.energy.unwrap().way_probes, 2 * 3 + 2);
+}
+
+#[test]
+fn csv_energy_test() {
+    use csimlib::report::{self, Destination, Output};
+    use csimlib::sweep;
+
+    let input = std::sync::Arc::new(common::reads(vec![0, 0, 0, 64]));
+    let options = csimlib::RunOptions {
+        technology: Some(Default::default()),
+        seed: Some(5),
+        ..Default::default()
+    };
+    let params = csimlib::RunParams {
+        adress_bits: 16,
+        options: options.clone(),
+        ..common::params(4, 4, 2, input.clone())
+    };
+    let cache = csimlib::run_with(&params);
+    let energy = cache.energy.clone().unwrap();
+
+    let path = std::env::temp_dir().join(format!("csim-csv-energy-{}.csv", std::process::id()));
+    let destination = Destination {
+        path: Some(path.to_str().unwrap().to_owned()),
+        append: false,
+    };
+    let read = || {
+        let written = std::fs::read_to_string(&path).unwrap();
+        written.lines().map(str::to_owned).collect::<Vec<_>>()
+    };
+
+    report::write(&cache, Output::Csv, 1, &destination, None).unwrap();
+    let lines = read();
+    assert_eq!(lines[0], report::csv_header());
+    assert!(report::csv_header().ends_with(",energy_pj,energy_per_access_pj,area_mm2"));
+    assert!(lines[1].ends_with(&format!(
+        ",{},{},{}",
+        energy.total(),
+        energy.per_access(4),
+        energy.area
+    )));
+    assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+
+    // Sem arquivo de tecnologia as colunas continuam lá, vazias, e as linhas
+    // das duas simulações podem ir para o mesmo arquivo.
+    let plain = csimlib::run_with(&csimlib::RunParams {
+        options: Default::default(),
+        ..params.clone()
+    });
+    let appended = Destination {
+        append: true,
+        ..destination.clone()
+    };
+    report::write(&plain, Output::Csv, 1, &appended, None).unwrap();
+    let mixed = read();
+    assert_eq!(mixed.len(), 3);
+    assert!(mixed[2].ends_with(",,,"));
+    assert_eq!(mixed[2].split(',').count(), mixed[0].split(',').count());
+
+    // No sweep as colunas da busca vêm depois das de energia.
+    let results = sweep::run(
+        &[sweep::Config::new(
+            4,
+            4,
+            2,
+            csimlib::cache::ReplacementPolicy::Lru,
+        )],
+        &input,
+        16,
+        1,
+        &options,
+    );
+    sweep::write_results(&results, Output::Csv, &destination, None).unwrap();
+    assert_eq!(read(), lines);
+
+    let search = csimlib::search::Search {
+        budget: 1 << 20,
+        area: false,
+        latency: None,
+        adress_bits: 16,
+    };
+    sweep::write_results(&results, Output::Csv, &destination, Some(&search)).unwrap();
+    let searched = read();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        searched[0],
+        format!("{},pareto_optimal,best_under_budget", lines[0])
+    );
+    assert!(searched[1].starts_with(&format!("{},", lines[1])));
+}
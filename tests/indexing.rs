@@ -0,0 +1,136 @@
+mod common;
+
+// Blocos de 4 bytes.
+fn params(nsets: usize, assoc: usize, blocks: &[u64]) -> csimlib::RunParams {
+    common::params(
+        nsets,
+        4,
+        assoc,
+        common::reads(blocks.iter().map(|block| block * 4)),
+    )
+}
+
+#[test]
+fn modulo_index_test() {
+    use csimlib::indexing::{largest_prime, IndexFunction, Indexer};
+
+    assert_eq!(largest_prime(1536), 1531);
+    assert_eq!(largest_prime(12), 11);
+    assert_eq!(largest_prime(1), 1);
+    assert_eq!(Indexer::new(&IndexFunction::Modulo, 3, 4).split(7), (1, 2));
+    assert_eq!(
+        Indexer::new(&IndexFunction::PrimeModulo, 12, 4).split(23),
+        (1, 2)
+    );
+
+    // 3 conjuntos diretamente mapeados: 0, 3 e 6 brigam pelo conjunto 0.
+    let mut params = params(3, 1, &[0, 3, 6, 0, 1, 2, 1, 2]);
+    params.options.index = IndexFunction::Modulo;
+    let cache = csimlib::run_with(&params);
+    assert_eq!(cache.performance.hits, 2);
+    assert_eq!(cache.set_performance[0].misses, 4);
+    assert_eq!(cache.set_performance[1].hits, 1);
+    assert_eq!(cache.set_performance[2].hits, 1);
+}
+
+#[test]
+fn prime_modulo_capacity_test() {
+    use csimlib::indexing::IndexFunction;
+
+    // 12 conjuntos com primo 11: 22 blocos em ciclo enchem os 11 conjuntos
+    // alcançáveis e todos os misses depois disso são de capacidade.
+    let blocks: Vec<u64> = (0..110).map(|i| i % 22).collect();
+    let mut params = params(12, 1, &blocks);
+    params.options.index = IndexFunction::PrimeModulo;
+    let cache = csimlib::run_with(&params);
+    assert_eq!(cache.info.total_slots, 11);
+    assert_eq!(cache.info.size, 44);
+    assert_eq!(cache.performance.compulsory_misses, 11);
+    assert_eq!(cache.performance.capacity_misses, 99);
+    assert_eq!(cache.performance.conflict_misses, 0);
+
+    let config = csimlib::sweep::Config::from(&cache.info);
+    assert_eq!(
+        (config.nsets, config.total_slots(), config.size()),
+        (12, 11, 44)
+    );
+    assert!(
+        csimlib::report::json(&config, cache.kind, &cache.performance, 0)
+            .contains("\"size\": 44, \"total_slots\": 11")
+    );
+}
+
+#[test]
+fn modulo_matches_bit_slice_test() {
+    use csimlib::indexing::IndexFunction;
+
+    let blocks: Vec<u64> = (0..500).map(|i| (i * 37 + i * i) % 211).collect();
+    let mut params = params(16, 2, &blocks);
+    let bits = csimlib::run_with(&params).performance;
+    params.options.index = IndexFunction::Modulo;
+    let modulo = csimlib::run_with(&params).performance;
+    assert_eq!(bits.hits, modulo.hits);
+    assert_eq!(bits.misses, modulo.misses);
+
+    // Sem --index, nsets que não é potência de 2 usa módulo.
+    let params = csimlib::parse_and_validate(
+        "12",
+        "4",
+        "3",
+        "l",
+        "1",
+        csimlib::Either::Right(("100", "1".to_owned())),
+    )
+    .unwrap();
+    assert_eq!(params.options.index, IndexFunction::Modulo);
+    assert!(csimlib::parse_index("bits", None, None, 12, 4).is_err());
+}
+
+#[test]
+fn hashed_index_test() {
+    use csimlib::indexing::{IndexFunction, Indexer};
+
+    // 8 conjuntos: bloco 0b101_011_110 -> 110 ^ 011 ^ 101 = 000.
+    let xor = Indexer::new(&IndexFunction::XorFold, 8, 4);
+    assert_eq!(xor.split(0b101_011_110), (0, 0b101_011));
+
+    // Bits 2, 3 e 4 do endereço são o BitSlice de blocos de 4 bytes.
+    let select = Indexer::new(&IndexFunction::Select(vec![2, 3, 4]), 8, 4);
+    let matrix = Indexer::new(&IndexFunction::Matrix(vec![0b100, 0b1000, 0b10000]), 8, 4);
+    let bits = Indexer::new(&IndexFunction::BitSlice, 8, 4);
+    for block in 0..100 {
+        assert_eq!(select.split(block).0, bits.split(block).0);
+        assert_eq!(matrix.split(block).0, bits.split(block).0);
+    }
+
+    // Bits de offset não podem entrar no índice.
+    assert!(csimlib::parse_index("select", None, Some(&["1", "3", "4"]), 8, 4).is_err());
+    assert!(csimlib::parse_index("matrix", Some(&["0x4", "0x8"]), None, 8, 4).is_err());
+    // Linhas ou bits demais são erro, não um shift que estoura.
+    assert!(csimlib::parse_index("matrix", Some(&["0x20"; 64]), None, 16, 32).is_err());
+    assert!(csimlib::parse_index("select", None, Some(&["5"; 64]), 16, 32).is_err());
+    assert_eq!(
+        csimlib::parse_index("matrix", Some(&["0x14", "0x28", "0x50"]), None, 8, 4),
+        Ok(IndexFunction::Matrix(vec![0x14, 0x28, 0x50]))
+    );
+}
+
+#[test]
+fn xor_reduces_stride_conflicts_test() {
+    use csimlib::indexing::IndexFunction;
+
+    // Passo de 16 blocos numa cache de 16 conjuntos: tudo cai no conjunto 0.
+    let blocks: Vec<u64> = (0..4).cycle().take(64).map(|i| i * 16).collect();
+    let mut params = params(16, 2, &blocks);
+    let baseline = csimlib::run_with(&params);
+    assert_eq!(baseline.set_performance[0].conflicts, 62);
+
+    params.options.index = IndexFunction::XorFold;
+    let hashed = csimlib::run_with(&params);
+    assert_eq!(hashed.performance.conflict_misses, 0);
+    assert_eq!(hashed.performance.hits, 60);
+
+    let report = csimlib::report::index_report(&baseline, &hashed, &IndexFunction::XorFold, 1);
+    assert!(report.starts_with("index function xor vs bits\n"));
+    assert!(report.contains("conflict misses              62          0     1.0000"));
+}
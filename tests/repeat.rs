@@ -0,0 +1,46 @@
+mod common;
+
+fn random_params(repl: csimlib::cache::ReplacementPolicy) -> csimlib::RunParams {
+    csimlib::RunParams {
+        repl,
+        ..common::params(8, 4, 4, common::random_reads(3000, 512, 11))
+    }
+}
+
+#[test]
+fn repl_seed_test() {
+    let mut params = random_params(csimlib::cache::ReplacementPolicy::Random);
+    params.options.seed = Some(42);
+
+    let first = csimlib::run_with(&params);
+    let second = csimlib::run_with(&params);
+    assert_eq!(first.info.seed, 42);
+    assert_eq!(first.performance.hits, second.performance.hits);
+    assert_eq!(first.performance.misses, second.performance.misses);
+}
+
+#[test]
+fn repetitions_test() {
+    use csimlib::cache::ReplacementPolicy;
+    use csimlib::repeat::Repetitions;
+
+    let mut params = random_params(ReplacementPolicy::Random);
+    params.options.seed = Some(7);
+    let repetitions = Repetitions::run(&params, 5);
+    assert_eq!(repetitions.seeds, vec![7, 8, 9, 10, 11]);
+
+    let summary = repetitions.summary();
+    let (_, deviation, miss_rate) = summary
+        .iter()
+        .find(|(name, _, _)| *name == "miss_rate")
+        .unwrap();
+    assert!(*deviation > 0.0);
+    assert!(miss_rate.low() < miss_rate.value && miss_rate.value < miss_rate.high());
+
+    // Sem aleatoriedade todas as repetições dão o mesmo resultado.
+    let params = random_params(ReplacementPolicy::Lru);
+    for (_, deviation, estimate) in Repetitions::run(&params, 3).summary() {
+        assert!(deviation < 1e-12);
+        assert!(estimate.half_width < 1e-9);
+    }
+}
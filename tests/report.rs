@@ -0,0 +1,160 @@
+mod common;
+
+#[test]
+fn json_test() {
+    use csimlib::cache::{Cache, Kind, ReplacementPolicy};
+    use csimlib::report;
+    use csimlib::sweep::Config;
+
+    let mut cache = Cache::create_with_seed(2, 4, ReplacementPolicy::Random, 1, Kind::Both, 42);
+    cache.access_with(0, 1, 0);
+    cache.access_with(0, 1, 0);
+
+    assert_eq!(
+        report::json(
+            &Config::from(&cache.info),
+            cache.kind,
+            &cache.performance,
+            cache.info.seed
+        ),
+        concat!(
+            "{\"schema_version\": 1, ",
+            "\"config\": {\"nsets\": 2, \"bsize\": 4, \"assoc\": 1, \"repl\": \"random\", ",
+            "\"kind\": \"both\", \"size\": 8, \"total_slots\": 2, \"index\": \"bits\", ",
+            "\"organisation\": \"set-associative\", \"sub_block\": 4, \"compression\": null, ",
+            "\"value_model\": null, \"translation\": null}, ",
+            "\"seed\": 42, ",
+            "\"counters\": {\"accesses\": 2, \"hits\": 1, \"misses\": 1, ",
+            "\"compulsory_misses\": 1, \"capacity_misses\": 0, \"conflict_misses\": 0, ",
+            "\"split_accesses\": 0, \"slots_occupied\": 1}, ",
+            "\"rates\": {\"hit_rate\": 0.5, \"miss_rate\": 0.5, \"compulsory_fraction\": 1, ",
+            "\"capacity_fraction\": 0, \"conflict_fraction\": 0}}"
+        )
+    );
+
+    // Sem acessos as taxas não existem.
+    let empty = Cache::create_with_seed(2, 4, ReplacementPolicy::Lru, 1, Kind::Data, 0);
+    let json = report::json(
+        &Config::from(&empty.info),
+        empty.kind,
+        &empty.performance,
+        empty.info.seed,
+    );
+    assert!(json.contains("\"hit_rate\": null"));
+    assert!(json.contains("\"conflict_fraction\": null"));
+}
+
+#[test]
+fn config_index_test() {
+    use csimlib::indexing::IndexFunction;
+    use csimlib::report;
+    use csimlib::sweep::Config;
+
+    // O run_with guarda a função de índice na configuração da cache.
+    let params = csimlib::RunParams {
+        options: csimlib::RunOptions {
+            index: IndexFunction::Matrix(vec![0x10, 0x20]),
+            ..Default::default()
+        },
+        ..common::params(4, 4, 1, Vec::new())
+    };
+    let cache = csimlib::run_with(&params);
+    let config = Config::from(&cache.info);
+    assert_eq!(config.index, params.options.index);
+
+    let json = report::json(&config, cache.kind, &cache.performance, 0);
+    assert!(json.contains("\"index\": \"matrix [0x10, 0x20]\""));
+    // A vírgula da matriz não pode separar colunas no CSV.
+    let row = report::csv_row(&config, &cache.performance, 0, None);
+    assert!(row.contains(",0,\"matrix [0x10, 0x20]\","));
+    assert_eq!(
+        report::csv_header().split(',').count(),
+        row.split(',').count() - 1
+    );
+}
+
+#[test]
+fn format_perf_without_misses_test() {
+    use csimlib::cache::{Cache, Kind, ReplacementPolicy};
+
+    let mut cache = Cache::create(1, 4, ReplacementPolicy::Lru, 1, Kind::Both);
+    assert_eq!(cache.format_perf(1), "0, 0, 0, 0, 0, 0");
+
+    cache.access_with(0, 0, 0);
+    cache.performance = Default::default();
+    cache.access_with(0, 0, 0);
+    assert_eq!(cache.format_perf(1), "1, 1, 0, 0, 0, 0");
+}
+
+#[test]
+fn csv_append_test() {
+    use csimlib::cache::{Cache, Kind, ReplacementPolicy};
+    use csimlib::report::{self, Destination, Output};
+
+    let path = std::env::temp_dir().join(format!("csim-csv-append-{}.csv", std::process::id()));
+    let destination = Destination {
+        path: Some(path.to_str().unwrap().to_owned()),
+        append: true,
+    };
+    let _ = std::fs::remove_file(&path);
+
+    let mut cache = Cache::create_with_seed(4, 8, ReplacementPolicy::Fifo, 2, Kind::Both, 7);
+    report::write(&cache, Output::Csv, 1, &destination, None).unwrap();
+    cache.access_with(1, 3, 0);
+    report::write(&cache, Output::Csv, 1, &destination, None).unwrap();
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            report::csv_header(),
+            "4,8,2,fifo,64,0,0,0,0,0,0,0,0,0,0,0,7,bits,set-associative,8,,,,,,,,,,,",
+            "4,8,2,fifo,64,1,0,1,1,0,0,0,1,1,0,0,7,bits,set-associative,8,,,,,,,,,,,",
+        ]
+    );
+}
+
+#[test]
+fn text_seed_test() {
+    use csimlib::cache::{Cache, Kind, ReplacementPolicy};
+    use csimlib::report::{self, Destination, Output};
+
+    let path = std::env::temp_dir().join(format!("csim-text-seed-{}.txt", std::process::id()));
+    let text = |cache: &Cache, verbosity| {
+        let destination = Destination {
+            path: Some(path.to_str().unwrap().to_owned()),
+            append: false,
+        };
+        report::write(cache, Output::Text, verbosity, &destination, None).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        written
+    };
+
+    // A seed aparece só com a política Random, e fora da verbosidade 1.
+    let cache = Cache::create_with_seed(4, 8, ReplacementPolicy::Random, 2, Kind::Both, 7);
+    assert!(text(&cache, 2).contains("seed = 7"));
+    assert!(!text(&cache, 1).contains("seed"));
+    let cache = Cache::create(4, 8, ReplacementPolicy::Lru, 2, Kind::Both);
+    assert!(!text(&cache, 2).contains("seed"));
+
+    // Com verbosidade 1 a saída continua sendo a linha só da especificação.
+    let mut cache = Cache::create(4, 8, ReplacementPolicy::Lru, 2, Kind::Both);
+    cache.access_with(1, 3, 0);
+    cache.access_with(1, 3, 0);
+    assert_eq!(text(&cache, 1), "2, 0.5, 0.5, 1, 0, 0\n");
+}
+
+#[test]
+fn coefficient_of_variation_test() {
+    use csimlib::report::coefficient_of_variation;
+
+    assert_eq!(coefficient_of_variation(&[]), 0.0);
+    assert_eq!(coefficient_of_variation(&[0, 0]), 0.0);
+    assert_eq!(coefficient_of_variation(&[5, 5, 5]), 0.0);
+    // Média 2, desvio padrão 2.
+    assert!((coefficient_of_variation(&[0, 4]) - 1.0).abs() < 1e-12);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+fn random_params(len: usize, adresses: u32, seed: u64) -> csimlib::RunParams {
+    common::params(16, 4, 2, common::random_reads(len, adresses, seed))
+}
+
+#[test]
+fn set_sampling_test() {
+    use csimlib::sampling::{Sampling, Summary};
+
+    let mut params = random_params(2000, 512, 3);
+    let full = csimlib::run_with(&params);
+
+    // Amostrar todos os conjuntos é a simulação completa, sem incerteza.
+    params.options.sampling = Some(Sampling::Sets { every: 1 });
+    let cache = csimlib::run_with(&params);
+    let summary = Summary::compute(&cache, &params).unwrap();
+    assert_eq!(summary.units, 16);
+    assert_eq!(summary.accesses.value, full.performance.accesses as f64);
+    assert_eq!(summary.misses.value, full.performance.misses as f64);
+    assert_eq!(summary.miss_rate.half_width, 0.0);
+
+    // Os conjuntos amostrados se comportam igual aos da simulação completa.
+    params.options.sampling = Some(Sampling::Sets { every: 4 });
+    let cache = csimlib::run_with(&params);
+    for (index, set) in cache.set_performance.iter().enumerate() {
+        if index % 4 == 0 {
+            assert_eq!(set.hits, full.set_performance[index].hits);
+            assert_eq!(set.misses, full.set_performance[index].misses);
+        } else {
+            assert_eq!(set.accesses(), 0);
+        }
+    }
+
+    let summary = Summary::compute(&cache, &params).unwrap();
+    let full_miss_rate = full.performance.misses as f64 / full.performance.accesses as f64;
+    assert_eq!(summary.units, 4);
+    assert!(summary.miss_rate.half_width > 0.0);
+    assert!(
+        summary.miss_rate.low() <= full_miss_rate && full_miss_rate <= summary.miss_rate.high()
+    );
+}
+
+#[test]
+fn time_sampling_test() {
+    use csimlib::sampling::{Phase, Sampling, Summary};
+
+    let sampling = Sampling::Time {
+        period: 10,
+        warmup: 2,
+        length: 3,
+    };
+    assert_eq!(sampling.phase(11), Phase::Warmup);
+    assert_eq!(
+        sampling.phase(12),
+        Phase::Measure {
+            first: true,
+            last: false
+        }
+    );
+    assert_eq!(sampling.phase(15), Phase::Skip);
+
+    let mut params = random_params(95, 512, 5);
+    params.options.sampling = Some(sampling);
+    let cache = csimlib::run_with(&params);
+
+    // 10 janelas de 3 acessos medidos, a última (90..95) ainda cabe inteira.
+    assert_eq!(cache.samples.len(), 10);
+    assert!(cache.samples.iter().all(|sample| sample.accesses == 3));
+    assert_eq!(cache.performance.accesses, 30);
+    assert_eq!(cache.warmup_performance.accesses, 20);
+
+    let summary = Summary::compute(&cache, &params).unwrap();
+    assert_eq!(summary.population, 95.0 / 3.0);
+    assert!((summary.accesses.value - 95.0).abs() < 1e-9);
+}
+
+#[test]
+fn estimate_test() {
+    use csimlib::stats;
+
+    let estimate = stats::mean_estimate(&[1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(estimate.value, 2.5);
+    // t(3) * s / sqrt(n) = 3.182 * 1.291 / 2
+    assert!((estimate.half_width - 2.054).abs() < 1e-3);
+    assert!(stats::mean_estimate(&[1.0]).half_width.is_infinite());
+
+    let ratio = stats::ratio_estimate(&[1.0, 2.0], &[10.0, 10.0], 2.0);
+    assert_eq!(ratio.value, 0.15);
+    assert_eq!(ratio.half_width, 0.0);
+}
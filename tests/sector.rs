@@ -0,0 +1,81 @@
+mod common;
+
+fn params(adresses: &[(u64, csimlib::trace::AccessType)]) -> csimlib::RunParams {
+    common::params(
+        1,
+        32,
+        1,
+        common::accesses(adresses.iter().map(|&(adress, access)| (access, adress))),
+    )
+}
+
+#[test]
+fn parse_sub_block_test() {
+    assert_eq!(csimlib::parse_sub_block("8", 32), Ok(8));
+    assert_eq!(csimlib::parse_sub_block("32", 32), Ok(32));
+    assert!(csimlib::parse_sub_block("6", 32).is_err());
+    assert!(csimlib::parse_sub_block("64", 32).is_err());
+    assert!(csimlib::parse_sub_block("1", 128).is_err());
+}
+
+#[test]
+fn sector_test() {
+    use csimlib::trace::AccessType::{Read, Write};
+
+    let mut params = params(&[
+        (0, Read),
+        (4, Read),
+        (8, Write),
+        (0, Read),
+        (32, Read),
+        (0, Read),
+    ]);
+    params.options.sub_block = Some(8);
+    let cache = csimlib::run_with(&params);
+    let perf = &cache.performance;
+
+    // 0: miss do setor, 4: hit no mesmo sub-bloco, 8: miss do sub-bloco,
+    // 0: hit, 32 e 0 brigam pela única linha.
+    assert_eq!(perf.accesses, 6);
+    assert_eq!(perf.hits, 2);
+    assert_eq!(perf.misses, 4);
+    assert_eq!(perf.compulsory_misses, 1);
+    assert_eq!(perf.sub_block_misses, 1);
+    assert_eq!(
+        perf.compulsory_misses + perf.capacity_misses + perf.conflict_misses,
+        3
+    );
+
+    // Saem 3 setores: o primeiro com 2 sub-blocos válidos (um sujo), os outros
+    // dois com 1, incluindo o que ficou na cache no fim.
+    assert_eq!(perf.unfetched_bytes, (2 + 3 + 3) * 8);
+    assert_eq!(perf.written_back_bytes, 8);
+
+    let report = csimlib::report::sector_report(&cache).unwrap();
+    assert!(report.contains("sub-block misses    = 1"));
+    assert!(report.contains("fetched bytes       = 32"));
+
+    // O tamanho do sub-bloco vai para a configuração do JSON e do CSV.
+    let config = csimlib::sweep::Config::from(&cache.info);
+    assert_eq!(config.sub_block, 8);
+    assert!(csimlib::report::json(&config, cache.kind, perf, 0).contains("\"sub_block\": 8,"));
+}
+
+#[test]
+fn sector_split_test() {
+    use csimlib::trace::AccessType::Read;
+
+    // Um acesso de 8 bytes a partir do byte 2 toca 3 sub-blocos de 4.
+    let mut params = params(&[(2, Read)]);
+    std::sync::Arc::get_mut(&mut params.input).unwrap()[0].size = 8;
+    params.options.sub_block = Some(4);
+    let cache = csimlib::run_with(&params);
+    assert_eq!(cache.performance.accesses, 3);
+    assert_eq!(cache.performance.misses, 3);
+    assert_eq!(cache.performance.sub_block_misses, 2);
+    assert_eq!(cache.performance.unfetched_bytes, 5 * 4);
+
+    // Sem sub-blocos o relatório não tem a seção de setores.
+    params.options.sub_block = None;
+    assert!(csimlib::report::sector_report(&csimlib::run_with(&params)).is_none());
+}
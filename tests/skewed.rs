@@ -0,0 +1,129 @@
+mod common;
+
+// Blocos de 4 bytes numa cache skewed.
+fn params(nsets: usize, assoc: usize, blocks: &[u64]) -> csimlib::RunParams {
+    let mut params = common::params(
+        nsets,
+        4,
+        assoc,
+        common::reads(blocks.iter().map(|block| block * 4)),
+    );
+    params.options.organisation = csimlib::cache::Organisation::Skewed;
+    params
+}
+
+#[test]
+fn skewed_indexes_test() {
+    use csimlib::skewed::indexes;
+
+    // 8 conjuntos: A1 = 0b011, A2 = 0b110.
+    let block = 0b110_011;
+    assert_eq!(indexes(block, 8, 3), vec![0b101, 0b110, 0b000]);
+    assert_eq!(indexes(block, 1, 2), vec![0, 0]);
+
+    // Depois das rotações entra um hash do bloco sem A1, igual para blocos
+    // que só mudam em A1.
+    let extra =
+        |block: u64| indexes(block, 8, 4)[3] as u64 ^ (block & 0b111) ^ (block >> 3 & 0b111);
+    assert_eq!(extra(0b001_110_011), extra(0b001_110_101));
+}
+
+#[test]
+fn skewed_functions_are_distinct_test() {
+    use csimlib::skewed::indexes;
+
+    // Mesmo com mais vias que bits de índice, cada par de vias manda algum
+    // bloco para conjuntos diferentes.
+    for &(nsets, assoc) in &[(2, 4), (4, 4), (8, 3), (16, 8), (64, 16)] {
+        // Blocos espalhados pelos 64 bits, para as vias que usam os pedaços
+        // mais altos.
+        let table: Vec<Vec<usize>> = (0..1u64 << 12)
+            .map(|i| indexes(i.wrapping_mul(0x9e37_79b9_7f4a_7c15), nsets, assoc))
+            .collect();
+        for a in 0..assoc {
+            for b in a + 1..assoc {
+                assert!(
+                    table.iter().any(|ways| ways[a] != ways[b]),
+                    "{} sets x {} ways: ways {} and {} are the same function",
+                    nsets,
+                    assoc,
+                    a,
+                    b
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn skewed_functions_with_short_adresses_test() {
+    use csimlib::skewed::indexes;
+
+    // 1024 conjuntos e blocos de 4 bytes em endereços de 32 bits: os blocos
+    // têm só 3 pedaços de 10 bits, e as vias 20 a 29 não podem repetir os
+    // conflitos das vias 10 a 19.
+    let (nsets, assoc) = (1024, 30);
+    let table: Vec<Vec<usize>> = (0..1u64 << 14)
+        .map(|i| indexes(i.wrapping_mul(0x9e37_79b9) & ((1 << 30) - 1), nsets, assoc))
+        .collect();
+    for a in 0..assoc {
+        for b in a + 1..assoc {
+            // Algum par de blocos briga pelo conjunto na via a e não na b.
+            let mut seen = std::collections::HashMap::new();
+            let differs = table
+                .iter()
+                .any(|ways| *seen.entry(ways[a]).or_insert(ways[b]) != ways[b]);
+            assert!(differs, "ways {} and {} have the same conflicts", a, b);
+        }
+    }
+}
+
+#[test]
+fn skewed_avoids_stride_conflicts_test() {
+    // Passo de 16 blocos: 4 blocos no conjunto 0 de uma cache 2-way.
+    let blocks: Vec<u64> = (0..4).cycle().take(64).map(|i| i * 16).collect();
+    let mut params = params(16, 2, &blocks);
+
+    let skewed = csimlib::run_with(&params);
+    assert_eq!(skewed.performance.misses, 4);
+    assert_eq!(skewed.performance.hits, 60);
+    // A organização vai para a configuração do JSON e do CSV.
+    let config = csimlib::sweep::Config::from(&skewed.info);
+    assert_eq!(config.organisation, csimlib::cache::Organisation::Skewed);
+    assert!(
+        csimlib::report::json(&config, skewed.kind, &skewed.performance, 0)
+            .contains("\"organisation\": \"skewed\"")
+    );
+
+    params.options.organisation = csimlib::cache::Organisation::SetAssociative;
+    let conventional = csimlib::run_with(&params);
+    assert_eq!(conventional.performance.misses, 64);
+}
+
+#[test]
+fn skewed_lru_test() {
+    use csimlib::cache::{AccessResult, Cache, Kind, MissTypes, ReplacementPolicy};
+
+    let mut cache = Cache::create(2, 4, ReplacementPolicy::Lru, 2, Kind::Both);
+    // Os três blocos disputam o conjunto 0 nas duas vias.
+    assert_eq!(
+        cache.access_skewed(&[0, 0], 1),
+        AccessResult::Miss(MissTypes::Compulsory)
+    );
+    assert_eq!(
+        cache.access_skewed(&[0, 0], 2),
+        AccessResult::Miss(MissTypes::Compulsory)
+    );
+    assert_eq!(cache.access_skewed(&[0, 0], 1), AccessResult::Hit);
+    // 2 é o menos usado recentemente.
+    assert_eq!(
+        cache.access_skewed(&[0, 0], 3),
+        AccessResult::Miss(MissTypes::Conflict)
+    );
+    assert_eq!(cache.access_skewed(&[0, 0], 1), AccessResult::Hit);
+    assert_eq!(
+        cache.access_skewed(&[0, 0], 2),
+        AccessResult::Miss(MissTypes::Conflict)
+    );
+    assert_eq!(cache.performance.slots_occupied, 2);
+}
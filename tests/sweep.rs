@@ -0,0 +1,140 @@
+mod common;
+
+#[test]
+fn sweep_matches_run_with_test() {
+    use csimlib::cache::ReplacementPolicy;
+    use csimlib::sweep;
+
+    let input = std::sync::Arc::new(common::random_reads(2000, 4096, 3));
+
+    let configs = sweep::configurations(
+        &[16, 64],
+        &[4, 16],
+        &[1, 4],
+        &[ReplacementPolicy::Lru, ReplacementPolicy::Fifo],
+    );
+    assert_eq!(configs.len(), 16);
+
+    let results = sweep::run(&configs, &input, 32, 3, &Default::default());
+    assert_eq!(results.len(), configs.len());
+    for (result, config) in results.iter().zip(&configs) {
+        assert_eq!(result.config, *config);
+
+        let params = csimlib::RunParams {
+            repl: config.repl,
+            ..common::params(config.nsets, config.bsize, config.assoc, input.clone())
+        };
+        let perf = csimlib::run_with(&params).performance;
+        assert_eq!(result.performance.misses, perf.misses, "{:?}", config);
+    }
+}
+
+#[test]
+fn sweep_options_test() {
+    let (configs, jobs) =
+        csimlib::parse_sweep_options(&["1..8"], &["4", "16"], &["2"], &["lru"], Some("2"), 32)
+            .unwrap();
+
+    assert_eq!(jobs, 2);
+    let nsets: Vec<usize> = configs.iter().map(|config| config.nsets).collect();
+    assert_eq!(nsets, vec![1, 1, 2, 2, 4, 4, 8, 8]);
+
+    // 2^13 conjuntos de 16 bytes não cabem em 16 bits.
+    let (configs, _) =
+        csimlib::parse_sweep_options(&["8192"], &["8", "16"], &["1"], &["lru"], None, 16).unwrap();
+    assert_eq!(configs.len(), 1);
+
+    assert!(csimlib::parse_sweep_options(&["8..2"], &["4"], &["1"], &["lru"], None, 32).is_err());
+
+    // Uma faixa até a maior potência de 2 termina sem estourar.
+    let largest = format!("1..{}", 1usize << (usize::BITS - 1));
+    let (configs, _) =
+        csimlib::parse_sweep_options(&["1"], &["4"], &[&largest], &["lru"], None, 32).unwrap();
+    assert_eq!(configs.len(), usize::BITS as usize);
+    assert_eq!(configs.last().unwrap().assoc, 1 << (usize::BITS - 1));
+}
+
+#[test]
+fn pareto_frontier_test() {
+    use csimlib::cache::{Performance, ReplacementPolicy};
+    use csimlib::search::Search;
+    use csimlib::sweep::{Config, SweepResult};
+
+    let result = |nsets, assoc, misses| SweepResult {
+        config: Config::new(nsets, 4, assoc, ReplacementPolicy::Lru),
+        performance: Performance {
+            accesses: 100,
+            misses,
+            ..Default::default()
+        },
+        seed: 0,
+        sampled: None,
+        energy: None,
+    };
+    let results = vec![
+        result(4, 1, 50),  // 16 bytes
+        result(2, 2, 60),  // 16 bytes, dominada pela de cima
+        result(8, 1, 30),  // 32 bytes
+        result(4, 2, 20),  // 32 bytes
+        result(16, 1, 25), // 64 bytes, menos associativa que a de cima
+        result(32, 1, 5),  // 128 bytes, fora do orçamento
+    ];
+
+    let search = Search {
+        budget: 64,
+        area: false,
+        latency: None,
+        adress_bits: 32,
+    };
+    assert_eq!(search.pareto_frontier(&results), vec![0, 2, 3, 4]);
+    assert_eq!(search.best(&results), Some(3));
+
+    // No JSON a fronteira vai nas próprias linhas, que continuam sendo só
+    // objetos JSON.
+    let path = std::env::temp_dir().join(format!("csim-pareto-{}.json", std::process::id()));
+    let destination = csimlib::report::Destination {
+        path: Some(path.to_str().unwrap().to_owned()),
+        append: false,
+    };
+    csimlib::sweep::write_results(
+        &results,
+        csimlib::report::Output::Json,
+        &destination,
+        Some(&search),
+    )
+    .unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines.len(), results.len());
+    assert!(lines
+        .iter()
+        .all(|line| line.starts_with('{') && line.ends_with('}')));
+    assert!(lines[3].ends_with("\"pareto_optimal\": true, \"best_under_budget\": true}"));
+    assert!(lines[1].ends_with("\"pareto_optimal\": false, \"best_under_budget\": false}"));
+
+    let search = Search {
+        budget: 8,
+        ..search
+    };
+    assert_eq!(search.pareto_frontier(&results), Vec::<usize>::new());
+    assert_eq!(search.best(&results), None);
+}
+
+#[test]
+fn search_options_test() {
+    let budget = |budget| {
+        csimlib::parse_search_options(Some(budget), false, None, 32)
+            .map(|search| search.unwrap().budget)
+    };
+
+    assert_eq!(budget("4096"), Ok(4096));
+    assert_eq!(budget("32K"), Ok(32 << 10));
+    assert_eq!(budget("1MiB"), Ok(1 << 20));
+    assert!(budget("32Q").is_err());
+    assert_eq!(
+        csimlib::parse_search_options(None, false, None, 32),
+        Ok(None)
+    );
+}
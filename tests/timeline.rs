@@ -0,0 +1,63 @@
+mod common;
+
+#[test]
+fn run_with_windows_test() {
+    let mut params = common::params(4, 4, 1, common::reads(vec![0, 0, 0, 4, 8, 12, 0]));
+    params.options.window = Some(3);
+
+    let cache = csimlib::run_with(&params);
+    let windows: Vec<(usize, usize, usize, usize, usize)> = cache
+        .windows
+        .iter()
+        .map(|window| {
+            (
+                window.start,
+                window.length,
+                window.delta.hits,
+                window.delta.misses,
+                window.blocks,
+            )
+        })
+        .collect();
+    assert_eq!(
+        windows,
+        vec![(0, 3, 2, 1, 1), (3, 3, 0, 3, 3), (6, 1, 1, 0, 1)]
+    );
+
+    params.options.window = None;
+    assert!(csimlib::run_with(&params).windows.is_empty());
+}
+
+#[test]
+fn phase_detector_test() {
+    use csimlib::cache::Performance;
+    use csimlib::timeline::{PhaseDetector, Window};
+
+    let window = |misses, blocks| Window {
+        start: 0,
+        length: 100,
+        delta: Performance {
+            accesses: 100,
+            hits: 100 - misses,
+            misses,
+            ..Default::default()
+        },
+        blocks,
+    };
+    let windows = vec![
+        window(10, 20),
+        window(12, 22),
+        // Taxa de miss pula de 0.12 para 0.5.
+        window(50, 22),
+        window(52, 23),
+        // Working set dobra.
+        window(55, 50),
+    ];
+
+    let detector = PhaseDetector {
+        miss_rate_threshold: 0.1,
+        working_set_threshold: 0.5,
+    };
+    assert_eq!(detector.detect(&windows), vec![0, 2, 4]);
+    assert_eq!(detector.detect(&[]), Vec::<usize>::new());
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use csimlib::cache::ReplacementPolicy;
+use csimlib::tlb::{Allocation, Comparison, Mmu, Translation};
+
+fn translation(allocation: Allocation, entries: usize, page_size: usize) -> Translation {
+    Translation {
+        page_size,
+        allocation,
+        entries,
+        assoc: entries,
+        repl: ReplacementPolicy::Lru,
+        walk_cycles: 10,
+    }
+}
+
+#[test]
+fn translate_test() {
+    // As páginas físicas saem na ordem em que as virtuais aparecem e o
+    // deslocamento dentro da página não muda.
+    let mut mmu = Mmu::new(translation(Allocation::Sequential, 1, 4096), 4, 32, 0);
+    assert_eq!(mmu.translate(0x5000_0123, true), 0x123);
+    assert_eq!(mmu.translate(0x7000_1010, true), 0x1010);
+    assert_eq!(mmu.translate(0x5000_0fff, true), 0xfff);
+
+    // A TLB de uma entrada só erra, mas a página não muda de lugar.
+    let stats = mmu.stats();
+    assert_eq!(stats.tlb.accesses, 3);
+    assert_eq!(stats.tlb.misses, 3);
+    assert_eq!(stats.walk_cycles(), 30);
+    assert_eq!(stats.pages, 2);
+
+    // Com cores a página física fica com a cor da virtual.
+    let mut mmu = Mmu::new(translation(Allocation::Coloured, 4, 4096), 4, 32, 0);
+    assert_eq!(mmu.translate(0x5000_0000, true), 0);
+    assert_eq!(mmu.translate(0x7000_1000, true), 0x1000);
+    assert_eq!(mmu.translate(0x7000_5000, true), 0x5000);
+    assert_eq!(mmu.translate(0x0000_9000, true), 0x9000);
+
+    // Sem contar, a TLB aprende a página mas não muda os contadores.
+    let mut mmu = Mmu::new(translation(Allocation::Random, 4, 4096), 4, 32, 0);
+    let physical = mmu.translate(0x1234, false);
+    assert_eq!(mmu.translate(0x1234, true), physical);
+    assert_eq!(mmu.stats().tlb.accesses, 1);
+    assert_eq!(mmu.stats().tlb.hits, 1);
+}
+
+#[test]
+fn colouring_test() {
+    // Páginas do tamanho do bloco numa cache direta de 4 conjuntos: as 4
+    // páginas virtuais caem no conjunto 0, e em sequência ganham as páginas
+    // físicas 0 a 3, uma por conjunto.
+    let mut params = common::params(
+        4,
+        16,
+        1,
+        common::reads((0..40).map(|access| access % 4 * 64)),
+    );
+    params.options.translation = Some(translation(Allocation::Sequential, 4, 16));
+    let cache = csimlib::run_with(&params);
+    assert_eq!(cache.translation.unwrap().comparison, None);
+
+    params.options.compare_translation = true;
+    let cache = csimlib::run_with(&params);
+    let stats = cache.translation.unwrap();
+
+    assert_eq!(cache.performance.misses, 4);
+    assert_eq!(cache.performance.conflict_misses, 0);
+    assert_eq!(stats.colours, 4);
+    assert_eq!(stats.tlb.misses, 4);
+    assert_eq!(stats.walk_cycles(), 40);
+    assert_eq!(
+        stats.comparison,
+        Some(Comparison {
+            virtual_conflicts: 39,
+            allocation: Allocation::Coloured,
+            conflicts: 39,
+        })
+    );
+
+    // Com cores a cache se comporta como a indexada pelo endereço virtual.
+    params.options.translation = Some(translation(Allocation::Coloured, 4, 16));
+    let cache = csimlib::run_with(&params);
+    assert_eq!(cache.performance.conflict_misses, 39);
+    assert_eq!(
+        cache
+            .translation
+            .as_ref()
+            .unwrap()
+            .comparison
+            .unwrap()
+            .allocation,
+        Allocation::Random
+    );
+
+    // As opções da TLB entram na configuração.
+    let config = csimlib::sweep::Config::from(&cache.info);
+    assert_eq!(config.translation, params.options.translation);
+    let json = csimlib::report::json(&config, cache.kind, &cache.performance, 0);
+    assert!(json.contains(concat!(
+        "\"translation\": {\"page_size\": 16, \"allocation\": \"coloured\", ",
+        "\"tlb_entries\": 4, \"tlb_assoc\": 4, \"tlb_repl\": \"lru\", \"cycles_per_walk\": 10}"
+    )));
+    let row = csimlib::report::csv_row(&config, &cache.performance, 0, None);
+    assert!(row.contains(",16,coloured,4,4,lru,10,"));
+}
+
+#[test]
+fn parse_translation_test() {
+    let parse = |tlb, page_size| csimlib::parse_translation(tlb, page_size, None, None, 64, 32);
+
+    let translation = parse("64", None).unwrap();
+    assert_eq!(translation.assoc, 64);
+    assert_eq!(translation.repl, ReplacementPolicy::Lru);
+    assert_eq!(translation.page_size, 4096);
+    assert_eq!(translation.allocation, Allocation::Sequential);
+    assert_eq!(translation.walk_cycles, 30);
+
+    let translation = parse("64,4,fifo", Some("2M")).unwrap();
+    assert_eq!(translation.assoc, 4);
+    assert_eq!(translation.repl, ReplacementPolicy::Fifo);
+    assert_eq!(translation.page_size, 2 << 20);
+
+    assert!(parse("0", None).is_err());
+    assert!(parse("64,3", None).is_err());
+    assert!(parse("64,4,mru", None).is_err());
+    assert!(parse("64", Some("3K")).is_err());
+    assert!(parse("64", Some("32")).is_err());
+}
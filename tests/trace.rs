@@ -0,0 +1,178 @@
+#[test]
+fn decode_widths_and_endianness_test() {
+    use csimlib::trace;
+
+    let raw = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+
+    let mut format = trace::Format::default();
+    assert_eq!(
+        trace::decode(&raw, &format).unwrap(),
+        vec![0x0102_0304, 0x0506_0708]
+    );
+
+    format.width = trace::RecordWidth::W16;
+    format.endianness = trace::Endianness::Little;
+    assert_eq!(
+        trace::decode(&raw, &format).unwrap(),
+        vec![0x0201, 0x0403, 0x0605, 0x0807]
+    );
+
+    format.width = trace::RecordWidth::W64;
+    assert_eq!(
+        trace::decode(&raw, &format).unwrap(),
+        vec![0x0807_0605_0403_0201]
+    );
+}
+
+#[test]
+fn decode_trailing_bytes_test() {
+    use csimlib::trace;
+
+    let raw = [0x00, 0x00, 0x00, 0x14, 0xff, 0xff];
+
+    let mut format = trace::Format::default();
+    assert!(trace::decode(&raw, &format).is_err());
+
+    format.trailing = trace::TrailingBytes::Truncate;
+    assert_eq!(trace::decode(&raw, &format).unwrap(), vec![0x14]);
+}
+
+#[test]
+fn encode_parse_round_trip_test() {
+    use csimlib::trace::{self, AccessType, Encoding, Record};
+
+    let records = vec![
+        Record {
+            adress: 0x1000,
+            size: 1,
+            access: AccessType::Fetch,
+            value: None,
+        },
+        Record {
+            adress: 0x0ff8,
+            size: 8,
+            access: AccessType::Read,
+            value: None,
+        },
+        Record {
+            adress: 0xffff_ffff,
+            size: 4,
+            access: AccessType::Write,
+            value: Some(0xdead_beef),
+        },
+        // Com valor o din escreve o tamanho mesmo quando ele é 1.
+        Record {
+            adress: 0x20,
+            size: 1,
+            access: AccessType::Read,
+            value: Some(0x7f),
+        },
+    ];
+
+    for &encoding in &[Encoding::Din, Encoding::Compact] {
+        let format = trace::Format {
+            encoding,
+            ..Default::default()
+        };
+        let raw = trace::encode(&records, &format).unwrap();
+        assert_eq!(trace::parse(&raw, &format).unwrap(), records);
+    }
+
+    // Os formatos crus só guardam o endereço.
+    for &encoding in &[Encoding::Binary, Encoding::Text] {
+        let format = trace::Format {
+            encoding,
+            access_size: 2,
+            ..Default::default()
+        };
+        let raw = trace::encode(&records, &format).unwrap();
+        let parsed = trace::parse(&raw, &format).unwrap();
+        assert_eq!(parsed.len(), records.len());
+        for (parsed, record) in parsed.iter().zip(&records) {
+            assert_eq!(parsed.adress, record.adress);
+            assert_eq!(parsed.size, 2);
+            assert_eq!(parsed.access, AccessType::Read);
+        }
+    }
+}
+
+#[test]
+fn filter_test() {
+    use csimlib::trace::AccessType;
+
+    let filter = csimlib::parse_filter(&["w", "fetch"], Some("0x10"), Some("32")).unwrap();
+    let keeps = |adress, access| {
+        filter.keeps(&csimlib::trace::Record {
+            adress,
+            size: 1,
+            access,
+            value: None,
+        })
+    };
+
+    assert!(keeps(0x10, AccessType::Write));
+    assert!(keeps(32, AccessType::Fetch));
+    assert!(!keeps(32, AccessType::Read));
+    assert!(!keeps(33, AccessType::Write));
+    assert!(!keeps(0xf, AccessType::Fetch));
+
+    assert!(csimlib::parse_filter(&[], Some("20"), Some("10")).is_err());
+}
+
+#[test]
+#[ignore]
+// Só deve rodar se os arquivos estiverem presente.
+fn text_matches_binary_test() {
+    use csimlib::trace;
+
+    let binary = trace::readfile("testfiles/bin_1000.bin", &Default::default()).unwrap();
+    let text = trace::readfile(
+        "testfiles/bin_1000.txt",
+        &trace::Format {
+            encoding: trace::Encoding::Text,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(binary, text);
+}
+
+#[test]
+fn trace_stats_test() {
+    use csimlib::analysis::TraceStats;
+    use csimlib::trace::{AccessType, Record};
+
+    let record = |adress, access| Record {
+        adress,
+        size: 1,
+        access,
+        value: None,
+    };
+    let input = vec![
+        record(0, AccessType::Read),
+        record(4, AccessType::Read),
+        record(8, AccessType::Write),
+        record(12, AccessType::Fetch),
+        record(4, AccessType::Read),
+    ];
+
+    let stats = TraceStats::compute(&input, &[4, 16], 2);
+    assert_eq!(stats.length, 5);
+    assert_eq!((stats.reads, stats.writes, stats.fetches), (3, 1, 1));
+    assert_eq!(stats.unique_adresses, 4);
+    assert_eq!(stats.unique_blocks, vec![4, 1]);
+
+    let windows: Vec<(usize, Vec<usize>)> = stats
+        .working_set
+        .iter()
+        .map(|window| (window.start, window.blocks.clone()))
+        .collect();
+    assert_eq!(
+        windows,
+        vec![(0, vec![2, 1]), (2, vec![2, 1]), (4, vec![1, 1])]
+    );
+
+    assert_eq!(stats.strides.get(&4), Some(&3));
+    assert_eq!(stats.strides.get(&-8), Some(&1));
+}
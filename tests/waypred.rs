@@ -0,0 +1,61 @@
+mod common;
+
+fn make_params(records: &[(csimlib::trace::AccessType, u64)]) -> csimlib::RunParams {
+    common::params(1, 16, 4, common::accesses(records.iter().copied()))
+}
+
+#[test]
+fn mru_test() {
+    use csimlib::trace::AccessType::Read;
+    use csimlib::waypred::Predictor;
+
+    // Dois blocos alternados: o MRU erra todos os hits, um bloco repetido
+    // acerta todos.
+    let mut params = make_params(&[(Read, 0), (Read, 16), (Read, 0), (Read, 16), (Read, 0)]);
+    params.options.way_predictor = Some(Predictor::Mru);
+    let cache = csimlib::run_with(&params);
+    let predictor = cache.way_predictor.unwrap();
+    assert_eq!(predictor.misses, 2);
+    assert_eq!(predictor.correct, 0);
+    assert_eq!(predictor.mispredicted, 3);
+    // 5 buscas: cada uma lê a via prevista e depois as 4.
+    assert_eq!(predictor.probes(4), 5 * 5);
+    assert_eq!(predictor.parallel_probes(4), 5 * 4);
+
+    let mut params = make_params(&[(Read, 0), (Read, 4), (Read, 8), (Read, 12)]);
+    params.options.way_predictor = Some(Predictor::Mru);
+    params.options.warmup = Some(csimlib::Warmup::Accesses(1));
+    let predictor = csimlib::run_with(&params).way_predictor.unwrap();
+    assert_eq!((predictor.correct, predictor.misses), (3, 0));
+    assert_eq!(predictor.probes(4), 3);
+}
+
+#[test]
+fn pc_test() {
+    use csimlib::trace::AccessType::{Fetch, Read};
+    use csimlib::waypred::Predictor;
+
+    // Cada instrução sempre acessa o mesmo bloco, a tabela pelo PC acerta
+    // onde o MRU erra.
+    let records: Vec<_> = (0..10)
+        .flat_map(|_| {
+            vec![
+                (Fetch, 0x100),
+                (Read, 0x1000),
+                (Fetch, 0x110),
+                (Read, 0x2000),
+            ]
+        })
+        .collect();
+    let mut params = make_params(&records);
+
+    params.options.way_predictor = Some(Predictor::Mru);
+    let mru = csimlib::run_with(&params).way_predictor.unwrap();
+    params.options.way_predictor = Some(csimlib::parse_way_predictor("pc", Some("64")).unwrap());
+    let pc = csimlib::run_with(&params).way_predictor.unwrap();
+
+    assert_eq!(mru.correct, 0);
+    assert_eq!(pc.misses, 4);
+    assert_eq!(pc.correct, 36);
+    assert!(csimlib::parse_way_predictor("pc", Some("0")).is_err());
+}